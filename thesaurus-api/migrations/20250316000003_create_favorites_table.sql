@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS favorites
+(
+    id         UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    user_id    UUID         NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    word       VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ,
+    UNIQUE (user_id, word)
+);
+
+CREATE INDEX idx_favorites_user_id_updated_at ON favorites (user_id, updated_at);
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS word_lists
+(
+    id          UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    user_id     UUID         NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name        VARCHAR(255) NOT NULL,
+    description TEXT,
+    share_token VARCHAR(64) UNIQUE,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_word_lists_user_id ON word_lists (user_id);
+
+CREATE TABLE IF NOT EXISTS word_list_entries
+(
+    id         UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    list_id    UUID         NOT NULL REFERENCES word_lists (id) ON DELETE CASCADE,
+    word       VARCHAR(255) NOT NULL,
+    note       TEXT,
+    position   INTEGER      NOT NULL,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (list_id, word)
+);
+
+CREATE INDEX idx_word_list_entries_list_id_position ON word_list_entries (list_id, position);
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS organizations
+(
+    id         UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    name       VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations (id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS show_community_suggestions BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS suggestions
+(
+    id           UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    user_id      UUID         NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    word         VARCHAR(255) NOT NULL,
+    related_word VARCHAR(255) NOT NULL,
+    relation     VARCHAR(32)  NOT NULL,
+    language     VARCHAR(16)  NOT NULL,
+    status       VARCHAR(16)  NOT NULL DEFAULT 'pending',
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, word, related_word, relation, language)
+);
+
+CREATE INDEX idx_suggestions_word_language ON suggestions (word, language);
+CREATE INDEX idx_users_organization_id ON users (organization_id);
@@ -0,0 +1,39 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS role VARCHAR(16) NOT NULL DEFAULT 'user';
+
+CREATE TABLE IF NOT EXISTS proposals
+(
+    id          UUID PRIMARY KEY      DEFAULT uuid_generate_v4(),
+    user_id     UUID         NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    word        VARCHAR(255) NOT NULL,
+    language    VARCHAR(16)  NOT NULL,
+    field       VARCHAR(32)  NOT NULL,
+    action      VARCHAR(16)  NOT NULL,
+    value       TEXT         NOT NULL,
+    old_value   TEXT,
+    reason      TEXT,
+    status      VARCHAR(16)  NOT NULL DEFAULT 'pending',
+    merged_into UUID REFERENCES proposals (id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES users (id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    applied_at  TIMESTAMPTZ,
+    apply_error TEXT,
+    created_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_proposals_status_created_at ON proposals (status, created_at);
+CREATE INDEX idx_proposals_user_id ON proposals (user_id);
+
+CREATE TABLE IF NOT EXISTS moderation_events
+(
+    id           UUID PRIMARY KEY     DEFAULT uuid_generate_v4(),
+    subject_type VARCHAR(16) NOT NULL,
+    subject_id   UUID        NOT NULL,
+    actor_id     UUID REFERENCES users (id) ON DELETE SET NULL,
+    action       VARCHAR(16) NOT NULL,
+    note         TEXT,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_moderation_events_subject ON moderation_events (subject_type, subject_id, created_at);
@@ -0,0 +1,9 @@
+-- `token` now holds the SHA-256 hash of an opaque refresh token. Every
+-- rotation adds a row to the same family; revoking a session revokes the
+-- whole family.
+ALTER TABLE user_sessions
+    ADD COLUMN IF NOT EXISTS family_id  UUID NOT NULL DEFAULT uuid_generate_v4(),
+    ADD COLUMN IF NOT EXISTS rotated_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ;
+
+CREATE INDEX idx_user_sessions_family_id ON user_sessions (family_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_suggestions_word_language;
+
+CREATE INDEX idx_suggestions_lower_word_language ON suggestions (lower(word), language);
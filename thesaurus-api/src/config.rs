@@ -3,8 +3,33 @@ use serde::Deserialize;
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub jwt_secret: String,
-    pub jwt_expires_in: u64,
+    pub jwt_expires_in: i64,
     pub jwt_maxage: i64,
+    pub default_language: String,
+    pub history_retention_days: i64,
+    pub refresh_token_days: i64,
+    pub app_url: String,
+    pub mailer: String,
+    pub mail_from: String,
+    pub mail_dir: Option<String>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub oidc_issuer: Option<String>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
+    pub oidc_redirect_uri: Option<String>,
+    pub oidc_scopes: String,
+    pub rate_limit_backend: String,
+    pub rate_limit_lookup: u32,
+    pub rate_limit_auth: u32,
+    pub rate_limit_account: u32,
+    pub trust_proxy: bool,
+    pub word_cache_size: usize,
+    pub word_cache_ttl_secs: u64,
+    pub http_cache_max_age: u64,
+    pub admin_email: Option<String>,
 }
 
 impl Config {
@@ -13,12 +38,71 @@ impl Config {
             jwt_secret: std::env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
             jwt_expires_in: std::env::var("JWT_EXPIRES_IN")
                 .unwrap_or_else(|_| "60".to_string())
-                .parse::<u64>()
+                .parse::<i64>()
                 .expect("JWT_EXPIRES_IN must be a number"),
             jwt_maxage: std::env::var("JWT_MAXAGE")
                 .unwrap_or_else(|_| "60".to_string())
                 .parse::<i64>()
                 .expect("JWT_MAXAGE must be a number"),
+            default_language: std::env::var("DEFAULT_LANGUAGE")
+                .unwrap_or_else(|_| "en".to_string()),
+            history_retention_days: std::env::var("HISTORY_RETENTION_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse::<i64>()
+                .expect("HISTORY_RETENTION_DAYS must be a number"),
+            refresh_token_days: std::env::var("REFRESH_TOKEN_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse::<i64>()
+                .expect("REFRESH_TOKEN_DAYS must be a number"),
+            app_url: std::env::var("APP_URL")
+                .unwrap_or_else(|_| "http://localhost:5173".to_string()),
+            mailer: std::env::var("MAILER").unwrap_or_else(|_| "file".to_string()),
+            mail_from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Freesaurus <no-reply@localhost>".to_string()),
+            mail_dir: std::env::var("MAIL_DIR").ok(),
+            smtp_host: std::env::var("SMTP_HOST").ok(),
+            smtp_port: std::env::var("SMTP_PORT")
+                .unwrap_or_else(|_| "587".to_string())
+                .parse::<u16>()
+                .expect("SMTP_PORT must be a number"),
+            smtp_username: std::env::var("SMTP_USERNAME").ok(),
+            smtp_password: std::env::var("SMTP_PASSWORD").ok(),
+            oidc_issuer: std::env::var("OIDC_ISSUER").ok(),
+            oidc_client_id: std::env::var("OIDC_CLIENT_ID").ok(),
+            oidc_client_secret: std::env::var("OIDC_CLIENT_SECRET").ok(),
+            oidc_redirect_uri: std::env::var("OIDC_REDIRECT_URI").ok(),
+            oidc_scopes: std::env::var("OIDC_SCOPES")
+                .unwrap_or_else(|_| "openid email profile".to_string()),
+            rate_limit_backend: std::env::var("RATE_LIMIT_BACKEND")
+                .unwrap_or_else(|_| "memory".to_string()),
+            rate_limit_lookup: std::env::var("RATE_LIMIT_LOOKUP")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse::<u32>()
+                .expect("RATE_LIMIT_LOOKUP must be a number"),
+            rate_limit_auth: std::env::var("RATE_LIMIT_AUTH")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse::<u32>()
+                .expect("RATE_LIMIT_AUTH must be a number"),
+            rate_limit_account: std::env::var("RATE_LIMIT_ACCOUNT")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u32>()
+                .expect("RATE_LIMIT_ACCOUNT must be a number"),
+            trust_proxy: std::env::var("TRUST_PROXY")
+                .map(|value| value == "true" || value == "1")
+                .unwrap_or(false),
+            word_cache_size: std::env::var("WORD_CACHE_SIZE")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse::<usize>()
+                .expect("WORD_CACHE_SIZE must be a number"),
+            word_cache_ttl_secs: std::env::var("WORD_CACHE_TTL_SECS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse::<u64>()
+                .expect("WORD_CACHE_TTL_SECS must be a number"),
+            http_cache_max_age: std::env::var("HTTP_CACHE_MAX_AGE")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse::<u64>()
+                .expect("HTTP_CACHE_MAX_AGE must be a number"),
+            admin_email: std::env::var("ADMIN_EMAIL").ok(),
         }
     }
 }
@@ -1,10 +1,17 @@
-use crate::config::Config;
+use crate::{
+    config::Config,
+    services::{mailer::Mailer, oidc::OidcClient, rate_limit::RateLimitStore},
+};
 use meilisearch_sdk::client::Client as MeiliClient;
 use sqlx::postgres::PgPool;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub meili: MeiliClient,
     pub config: Config,
+    pub mailer: Arc<dyn Mailer>,
+    pub oidc: Option<Arc<OidcClient>>,
+    pub rate_limiter: Arc<dyn RateLimitStore>,
 }
@@ -1,3 +1,4 @@
+use crate::models::word::Correction;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -23,15 +24,27 @@ pub enum AppError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Forbidden")]
+    Forbidden,
+
     #[error("User already exists")]
     UserAlreadyExists,
 
     #[error("Invalid credentials")]
     InvalidCredentials,
 
+    #[error("Too many requests")]
+    TooManyRequests,
+
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Word '{word}' not found")]
+    WordNotFound {
+        word: String,
+        suggestions: Vec<Correction>,
+    },
+
     #[error("Bad request: {0}")]
     BadRequest(String),
 
@@ -50,13 +63,21 @@ impl IntoResponse for AppError {
             AppError::Bcrypt(ref e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             AppError::Meilisearch(ref e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
+            AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string()),
             AppError::UserAlreadyExists => {
                 (StatusCode::CONFLICT, "User already exists".to_string())
             }
             AppError::InvalidCredentials => {
                 (StatusCode::BAD_REQUEST, "Invalid credentials".to_string())
             }
+            AppError::TooManyRequests => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests".to_string(),
+            ),
             AppError::NotFound(ref e) => (StatusCode::NOT_FOUND, e.to_string()),
+            AppError::WordNotFound { ref word, .. } => {
+                (StatusCode::NOT_FOUND, format!("Word '{}' not found", word))
+            }
             AppError::BadRequest(ref e) => (StatusCode::BAD_REQUEST, e.to_string()),
             AppError::InternalServerError(ref e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
@@ -66,11 +87,16 @@ impl IntoResponse for AppError {
 
         tracing::error!("API Error: {:?}", self);
 
-        let body = Json(json!({
+        let mut body = json!({
             "status": "error",
             "message": error_message,
-        }));
+        });
+
+        // Lookup misses also say what the caller may have meant.
+        if let AppError::WordNotFound { suggestions, .. } = self {
+            body["suggestions"] = json!(suggestions);
+        }
 
-        (status, body).into_response()
+        (status, Json(body)).into_response()
     }
 }
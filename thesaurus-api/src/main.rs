@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use dotenv::dotenv;
@@ -15,6 +15,8 @@ mod middleware;
 mod models;
 mod routes;
 mod services;
+#[cfg(test)]
+mod testing;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -52,18 +54,235 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers(Any);
 
-    let app = Router::new()
-        // Health check
-        .route("/health", get(routes::health::health_check))
-        // Auth routes
+    let config = config::Config::from_env();
+    let mailer = services::mailer::from_config(&config)?;
+    let oidc = services::oidc::OidcClient::from_config(&config);
+
+    if let Some(oidc) = &oidc {
+        tracing::info!("OIDC login enabled for {}", oidc.issuer());
+    }
+
+    let rate_limiter = services::rate_limit::from_config(&config, &pool)?;
+
+    services::search::init_word_cache(&config);
+
+    let state = db::AppState {
+        db: pool.clone(),
+        meili: meili_client,
+        config,
+        mailer,
+        oidc,
+        rate_limiter,
+    };
+
+    if let Some(email) = &state.config.admin_email {
+        match services::moderation::bootstrap_admin(&pool, email).await? {
+            Some(user) => tracing::info!("{} is an admin (ADMIN_EMAIL)", user.email),
+            None => tracing::warn!(
+                "ADMIN_EMAIL is set, but nobody has signed up as {} yet; restart once they have",
+                email
+            ),
+        }
+    }
+
+    services::history::spawn_retention_task(pool.clone(), state.config.history_retention_days);
+    services::moderation::spawn_apply_task(pool.clone(), state.meili.clone());
+    services::rate_limit::spawn_prune_task(state.rate_limiter.clone());
+    services::search::spawn_index_version_task(state.meili.clone());
+
+    // Word lists, also reachable with an API key that has the `lists` scope
+    let list_routes = Router::new()
+        .route(
+            "/lists",
+            get(routes::word_lists::list).post(routes::word_lists::create),
+        )
+        .route(
+            "/lists/:id",
+            get(routes::word_lists::get)
+                .put(routes::word_lists::update)
+                .delete(routes::word_lists::delete),
+        )
+        .route(
+            "/lists/:id/entries",
+            post(routes::word_lists::add_list_entry),
+        )
+        .route(
+            "/lists/:id/entries/:entry_id",
+            put(routes::word_lists::update_list_entry)
+                .delete(routes::word_lists::delete_list_entry),
+        )
+        .route("/lists/:id/order", put(routes::word_lists::reorder))
+        .route(
+            "/lists/:id/share",
+            post(routes::word_lists::share).delete(routes::word_lists::unshare),
+        )
+        .route("/lists/:id/export", get(routes::word_lists::export))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::account,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth::auth_or_lists_key,
+        ));
+
+    // Routes for the signed-in user
+    let me_routes = Router::new()
+        .route(
+            "/favorites",
+            get(routes::favorites::list).post(routes::favorites::add),
+        )
+        .route(
+            "/favorites/:word",
+            get(routes::favorites::get).delete(routes::favorites::remove),
+        )
+        .route(
+            "/history",
+            get(routes::history::list).delete(routes::history::clear),
+        )
+        .route("/history/:id", delete(routes::history::delete_entry))
+        .route("/history/settings", put(routes::history::update_settings))
+        .route(
+            "/proposals",
+            get(routes::proposals::list).post(routes::proposals::create),
+        )
+        .route(
+            "/suggestions",
+            get(routes::suggestions::list).post(routes::suggestions::add),
+        )
+        .route("/suggestions/:id", delete(routes::suggestions::delete))
+        .route(
+            "/suggestions/settings",
+            put(routes::suggestions::update_settings),
+        )
+        .route(
+            "/api-keys",
+            get(routes::api_keys::list).post(routes::api_keys::create),
+        )
+        .route("/api-keys/:id", delete(routes::api_keys::revoke))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::account,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth::auth,
+        ))
+        .merge(list_routes);
+
+    // Moderation queue for editors and admins
+    let moderation_routes = Router::new()
+        .route("/proposals", get(routes::moderation::list_proposal_queue))
+        .route(
+            "/proposals/:id",
+            get(routes::moderation::get_proposal_with_events),
+        )
+        .route(
+            "/proposals/:id/approve",
+            post(routes::moderation::approve_proposal),
+        )
+        .route(
+            "/proposals/:id/reject",
+            post(routes::moderation::reject_proposal),
+        )
+        .route("/proposals/:id/merge", post(routes::moderation::merge))
+        .route(
+            "/suggestions",
+            get(routes::moderation::list_suggestion_queue),
+        )
+        .route(
+            "/suggestions/:id/approve",
+            post(routes::moderation::approve_suggestion),
+        )
+        .route(
+            "/suggestions/:id/reject",
+            post(routes::moderation::reject_suggestion),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::account,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth::moderator,
+        ));
+
+    let admin_routes = Router::new()
+        .route("/users/:id/role", put(routes::moderation::update_role))
+        .route(
+            "/users/:id/organization",
+            put(routes::organizations::update_membership),
+        )
+        .route(
+            "/organizations",
+            get(routes::organizations::list).post(routes::organizations::create),
+        )
+        .route("/organizations/:id", delete(routes::organizations::delete))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::account,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth::admin,
+        ));
+
+    // Sign-in, sign-out and account recovery, limited per client IP
+    let auth_routes = Router::new()
         .route("/auth/register", post(routes::auth::register))
         .route("/auth/login", post(routes::auth::login))
         .route("/auth/refresh", post(routes::auth::refresh_token))
-        // Thesaurus routes
+        .route("/auth/forgot-password", post(routes::auth::forgot_password))
+        .route("/auth/oidc/login", get(routes::oidc::login))
+        .route("/auth/oidc/callback", get(routes::oidc::callback))
+        .route(
+            "/auth/reset-password",
+            post(routes::auth::reset_password_with_token),
+        )
+        .route(
+            "/auth/verify-email",
+            post(routes::auth::verify_email_with_token),
+        )
+        .route(
+            "/auth/resend-verification",
+            post(routes::auth::resend_verification).route_layer(
+                axum::middleware::from_fn_with_state(state.clone(), middleware::auth::auth),
+            ),
+        )
+        .route(
+            "/auth/logout",
+            post(routes::auth::logout).route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                middleware::auth::auth,
+            )),
+        )
+        .route(
+            "/auth/logout-all",
+            post(routes::auth::logout_all).route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                middleware::auth::auth,
+            )),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::auth,
+        ));
+
+    // Thesaurus lookups. Signed-in users and API keys are limited on their
+    // own account, everyone else per client IP. Responses carry an ETag.
+    let lookup_routes = Router::new()
         .route("/api/search", get(routes::thesaurus::search))
+        .route("/api/suggest", get(routes::thesaurus::suggest))
+        .route("/api/spellcheck/:word", get(routes::thesaurus::spellcheck))
         .route("/api/word/:word", get(routes::thesaurus::get_word))
+        .route("/api/word/:word/senses", get(routes::thesaurus::get_senses))
+        .route("/api/batch/words", post(routes::thesaurus::batch_words))
+        .route("/api/translate/:word", get(routes::thesaurus::translate))
+        .route("/api/graph/:word", get(routes::thesaurus::get_graph))
+        .route("/api/similarity", get(routes::thesaurus::similarity))
         .route("/api/synonyms/:word", get(routes::thesaurus::get_synonyms))
         .route("/api/antonyms/:word", get(routes::thesaurus::get_antonyms))
         .route(
@@ -84,13 +303,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .route("/api/examples/:word", get(routes::thesaurus::get_examples))
         .route("/api/all/:word", get(routes::thesaurus::get_all_relations))
+        .route(
+            "/api/shared/lists/:token",
+            get(routes::word_lists::get_shared),
+        )
+        .route(
+            "/api/shared/lists/:token/export",
+            get(routes::word_lists::export_shared),
+        )
+        .route(
+            "/api/relations/:word",
+            get(routes::thesaurus::get_relations_by_type),
+        )
+        .route(
+            "/api/relation-types",
+            get(routes::thesaurus::list_relation_types),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::cache::conditional_get,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit::lookup,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::auth::optional_auth,
+        ));
+
+    let app = Router::new()
+        // Health check
+        .route("/health", get(routes::health::health_check))
+        .merge(auth_routes)
+        .merge(lookup_routes)
+        .nest("/api/me", me_routes)
+        .nest("/api/moderation", moderation_routes)
+        .nest("/api/admin", admin_routes)
         //middleware
         .layer(cors)
-        .with_state(db::AppState {
-            db: pool.clone(),
-            meili: meili_client,
-            config: config::Config::from_env(),
-        });
+        .with_state(state);
 
     let port = std::env::var("PORT")
         .ok()
@@ -114,7 +366,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Listening on {}", addr);
 
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
 
     Ok(())
@@ -0,0 +1,44 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{api_key::ApiKey, user::User},
+    services::api_keys::{find_api_key, touch_api_key},
+};
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// The user behind an `X-API-Key` header, together with the key they used.
+/// Scope checks are left to the caller.
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth {
+    pub user: User,
+    pub key: ApiKey,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for ApiKeyAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, AppError> {
+        let key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(AppError::Unauthorized)?;
+
+        let key = find_api_key(&state.db, key)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(key.user_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        touch_api_key(&state.db, &key.id);
+
+        Ok(Self { user, key })
+    }
+}
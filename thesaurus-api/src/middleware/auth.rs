@@ -1,14 +1,26 @@
-use crate::{db::AppState, error::AppError, models::user::User, services::auth::verify_token};
-use axum::{body::Body, extract::State, http::Request, middleware::Next, response::Response};
+use crate::{
+    db::AppState,
+    error::AppError,
+    middleware::api_key::{ApiKeyAuth, API_KEY_HEADER},
+    models::{session::SessionId, user::User},
+    services::{auth::verify_token, sessions::is_session_active},
+};
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
 use uuid::Uuid;
 
-pub async fn auth(
-    State(state): State<AppState>,
-    mut req: Request<Body>,
-    next: Next<Body>,
-) -> Result<Response, AppError> {
-    let token = req
-        .headers()
+/// Resolves the user and session behind the `Authorization: Bearer` header.
+/// Tokens of revoked or expired sessions are rejected.
+async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<(User, SessionId), AppError> {
+    let token = headers
         .get("Authorization")
         .and_then(|auth_header| auth_header.to_str().ok())
         .and_then(|auth_value| {
@@ -22,13 +34,205 @@ pub async fn auth(
     let claims = verify_token(&state.config, &token)?;
 
     let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized)?;
+    let session_id = Uuid::parse_str(&claims.sid).map_err(|_| AppError::Unauthorized)?;
+
+    if !is_session_active(&state.db, &user_id, &session_id).await? {
+        return Err(AppError::Unauthorized);
+    }
+
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
         .bind(user_id)
         .fetch_optional(&state.db)
         .await?
         .ok_or(AppError::Unauthorized)?;
 
+    Ok((user, SessionId(session_id)))
+}
+
+pub async fn auth(
+    State(state): State<AppState>,
+    mut req: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    let (user, session_id) = authenticate(&state, req.headers()).await?;
+
     req.extensions_mut().insert(user);
+    req.extensions_mut().insert(session_id);
+
+    Ok(next.run(req).await)
+}
+
+/// Like [`auth`], but also accepts an `X-API-Key` with the `lists` scope.
+pub async fn auth_or_lists_key(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    if !req.headers().contains_key(API_KEY_HEADER) {
+        return auth(State(state), req, next).await;
+    }
+
+    let req = with_api_key(&state, req, "lists").await?;
 
     Ok(next.run(req).await)
 }
+
+/// Resolves the `X-API-Key` header, checks that the key has `scope` and
+/// adds its user and key to the request extensions.
+async fn with_api_key(
+    state: &AppState,
+    req: Request<Body>,
+    scope: &str,
+) -> Result<Request<Body>, AppError> {
+    let (mut parts, body) = req.into_parts();
+    let ApiKeyAuth { user, key } = ApiKeyAuth::from_request_parts(&mut parts, state).await?;
+
+    if !key.has_scope(scope) {
+        return Err(AppError::Forbidden);
+    }
+
+    let mut req = Request::from_parts(parts, body);
+    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(key);
+
+    Ok(req)
+}
+
+/// Like [`auth`], but only lets editors and admins through.
+pub async fn moderator(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    authorize(&state, req, next, User::can_moderate).await
+}
+
+/// Like [`auth`], but only lets admins through.
+pub async fn admin(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    authorize(&state, req, next, User::is_admin).await
+}
+
+async fn authorize(
+    state: &AppState,
+    mut req: Request<Body>,
+    next: Next<Body>,
+    allowed: fn(&User) -> bool,
+) -> Result<Response, AppError> {
+    let (user, session_id) = authenticate(state, req.headers()).await?;
+
+    if !allowed(&user) {
+        return Err(AppError::Forbidden);
+    }
+
+    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(session_id);
+
+    Ok(next.run(req).await)
+}
+
+/// Like [`auth`], but lets anonymous requests through. Handlers can take an
+/// `Option<Extension<User>>` to act on signed-in users. An `X-API-Key` with
+/// the `read` scope also identifies the user.
+pub async fn optional_auth(
+    State(state): State<AppState>,
+    mut req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    if let Ok((user, session_id)) = authenticate(&state, req.headers()).await {
+        req.extensions_mut().insert(user);
+        req.extensions_mut().insert(session_id);
+    } else if req.headers().contains_key(API_KEY_HEADER) {
+        let (mut parts, body) = req.into_parts();
+
+        if let Ok(ApiKeyAuth { user, key }) =
+            ApiKeyAuth::from_request_parts(&mut parts, &state).await
+        {
+            if key.has_scope("read") {
+                parts.extensions.insert(user);
+                parts.extensions.insert(key);
+            }
+        }
+
+        req = Request::from_parts(parts, body);
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        services::api_keys::create_api_key,
+        testing::{create_user, test_pool, test_state},
+    };
+    use axum::{http::StatusCode, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    async fn lists_status(state: &AppState, key: Option<&str>) -> StatusCode {
+        let app = Router::new()
+            .route(
+                "/lists",
+                get(|Extension(user): Extension<User>| async move { user.name }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                auth_or_lists_key,
+            ))
+            .with_state(state.clone());
+
+        let mut request = Request::builder().uri("/lists");
+        if let Some(key) = key {
+            request = request.header(API_KEY_HEADER, key);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn lists_key_needs_the_lists_scope() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let state = test_state(pool.clone());
+        let user = create_user(&pool).await;
+
+        let (_, lists_key) = create_api_key(&pool, &user.id, "lists", &["lists".to_string()])
+            .await
+            .unwrap();
+        let (_, read_key) = create_api_key(&pool, &user.id, "read", &["read".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(lists_status(&state, Some(&lists_key)).await, StatusCode::OK);
+        assert_eq!(
+            lists_status(&state, Some(&read_key)).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_or_missing_keys_are_unauthorized() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let state = test_state(pool);
+
+        assert_eq!(
+            lists_status(&state, Some("fsk_unknown")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            lists_status(&state, Some("not-a-key")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(lists_status(&state, None).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{db::AppState, error::AppError, models::user::User};
+use axum::{
+    body::{boxed, Body, Full},
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Adds `ETag` and `Cache-Control` to successful GET responses and answers
+/// a matching `If-None-Match` with `304 Not Modified`. Signed-in callers
+/// may see their own suggestions, so their responses are marked private.
+pub async fn conditional_get(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    if req.method() != Method::GET {
+        return Ok(next.run(req).await);
+    }
+
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let private = req.extensions().get::<User>().is_some();
+
+    let cache_control = format!(
+        "{}, max-age={}",
+        if private { "private" } else { "public" },
+        state.config.http_cache_max_age
+    );
+
+    let response = next.run(req).await;
+
+    revalidate(response, if_none_match.as_ref(), &cache_control).await
+}
+
+/// Tags a `200 OK` response with its `ETag` and `cache_control` (unless it
+/// set its own), or swaps it for a `304 Not Modified` if the client already
+/// has it.
+async fn revalidate(
+    response: Response,
+    if_none_match: Option<&HeaderValue>,
+    cache_control: &str,
+) -> Result<Response, AppError> {
+    if response.status() != StatusCode::OK {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+    let etag = format!("\"{}\"", &hex::encode(Sha256::digest(&bytes))[..32]);
+
+    // Handlers may pick their own policy, e.g. for content that can be
+    // withdrawn.
+    let cache_control = match parts
+        .headers
+        .get(header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(own) => own.to_string(),
+        None => cache_control.to_string(),
+    };
+    let cache_control = cache_control.as_str();
+
+    if if_none_match
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, &etag))
+    {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        insert_headers(not_modified.headers_mut(), &etag, cache_control)?;
+
+        return Ok(not_modified);
+    }
+
+    insert_headers(&mut parts.headers, &etag, cache_control)?;
+
+    Ok(Response::from_parts(parts, boxed(Full::from(bytes))))
+}
+
+/// Weak comparison, as `If-None-Match` requires.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').any(|candidate| {
+        let candidate = candidate.trim();
+        candidate == "*" || candidate.trim_start_matches("W/") == etag
+    })
+}
+
+fn insert_headers(
+    headers: &mut HeaderMap,
+    etag: &str,
+    cache_control: &str,
+) -> Result<(), AppError> {
+    let value = |v: &str| {
+        HeaderValue::from_str(v).map_err(|e| AppError::InternalServerError(e.to_string()))
+    };
+
+    headers.insert(header::ETAG, value(etag)?);
+    headers.insert(header::CACHE_CONTROL, value(cache_control)?);
+    headers.insert(
+        header::VARY,
+        HeaderValue::from_static("Authorization, X-API-Key"),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETAG: &str = "\"abc\"";
+    const CACHE_CONTROL: &str = "public, max-age=300";
+
+    fn ok(body: &'static str) -> Response {
+        Response::new(boxed(Full::from(body)))
+    }
+
+    async fn etag_of(body: &'static str) -> HeaderValue {
+        let response = revalidate(ok(body), None, CACHE_CONTROL).await.unwrap();
+        response.headers()[header::ETAG].clone()
+    }
+
+    #[test]
+    fn etag_matches_strong_and_weak_tags() {
+        assert!(etag_matches("\"abc\"", ETAG));
+        assert!(etag_matches("W/\"abc\"", ETAG));
+        assert!(!etag_matches("\"abd\"", ETAG));
+        assert!(!etag_matches("abc", ETAG));
+    }
+
+    #[test]
+    fn etag_matches_lists_and_wildcard() {
+        assert!(etag_matches("\"xyz\", W/\"abc\"", ETAG));
+        assert!(etag_matches(" \"xyz\" ,\"abc\" ", ETAG));
+        assert!(!etag_matches("\"xyz\", \"uvw\"", ETAG));
+        assert!(etag_matches("*", ETAG));
+    }
+
+    #[tokio::test]
+    async fn revalidate_tags_ok_responses() {
+        let response = revalidate(ok("{}"), None, CACHE_CONTROL).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], CACHE_CONTROL);
+        assert_eq!(response.headers()[header::VARY], "Authorization, X-API-Key");
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(&body[..], b"{}");
+
+        // Same body, same tag; different body, different tag
+        assert_eq!(etag_of("{}").await, etag_of("{}").await);
+        assert_ne!(etag_of("{}").await, etag_of("[]").await);
+    }
+
+    #[tokio::test]
+    async fn revalidate_answers_matching_tags_with_304() {
+        let etag = etag_of("{}").await;
+        let response = revalidate(ok("{}"), Some(&etag), CACHE_CONTROL)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], CACHE_CONTROL);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(body.is_empty());
+
+        let stale = etag_of("[]").await;
+        let response = revalidate(ok("{}"), Some(&stale), CACHE_CONTROL)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn revalidate_keeps_the_handlers_cache_control() {
+        let mut response = ok("{}");
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+        let etag = etag_of("{}").await;
+        let response = revalidate(response, Some(&etag), CACHE_CONTROL)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    }
+
+    #[tokio::test]
+    async fn revalidate_leaves_errors_alone() {
+        let response = AppError::NotFound("Word 'x' not found".to_string()).into_response();
+        let response = revalidate(
+            response,
+            Some(&HeaderValue::from_static("*")),
+            CACHE_CONTROL,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+}
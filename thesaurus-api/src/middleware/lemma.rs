@@ -0,0 +1,55 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    services::morphy::{resolve, Resolution},
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Query},
+    http::request::Parts,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct LanguageParam {
+    lang: Option<String>,
+    from: Option<String>,
+}
+
+/// The `:word` path segment, lemmatized. `word` is the lemma to look up,
+/// so "geese" becomes "goose"; `lemma` says how it was resolved and is
+/// `None` when the segment was already a lemma (or nothing matched).
+/// `lang` is the language it was resolved in, taken from `lang` (or `from`
+/// on translations); handlers look the word up in the same language.
+pub struct LemmaPath {
+    pub word: String,
+    pub lang: String,
+    pub lemma: Option<Resolution>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for LemmaPath {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Path(word) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        let lang = Query::<LanguageParam>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|Query(param)| param.lang.or(param.from))
+            .unwrap_or_else(|| state.config.default_language.clone());
+
+        let lemma = resolve(&lang, &word);
+        let word = lemma
+            .as_ref()
+            .map_or(word, |resolution| resolution.lemma.clone());
+
+        Ok(Self { word, lang, lemma })
+    }
+}
@@ -1 +1,5 @@
+pub mod api_key;
 pub mod auth;
+pub mod cache;
+pub mod lemma;
+pub mod rate_limit;
@@ -0,0 +1,165 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{api_key::ApiKey, user::User},
+    services::rate_limit::{Decision, RateLimit},
+};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::{IpAddr, SocketAddr};
+
+/// Limits thesaurus lookups (`RATE_LIMIT_LOOKUP`).
+pub async fn lookup(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let per_minute = state.config.rate_limit_lookup;
+    limit(&state, req, next, "lookup", per_minute).await
+}
+
+/// Limits sign-in and account recovery (`RATE_LIMIT_AUTH`).
+pub async fn auth(State(state): State<AppState>, req: Request<Body>, next: Next<Body>) -> Response {
+    let per_minute = state.config.rate_limit_auth;
+    limit(&state, req, next, "auth", per_minute).await
+}
+
+/// Limits the signed-in user's own routes (`RATE_LIMIT_ACCOUNT`).
+pub async fn account(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let per_minute = state.config.rate_limit_account;
+    limit(&state, req, next, "account", per_minute).await
+}
+
+/// Takes a token from the caller's bucket for `group`. A limit of zero
+/// turns the group off, and requests go through if the store is down.
+async fn limit(
+    state: &AppState,
+    req: Request<Body>,
+    next: Next<Body>,
+    group: &str,
+    per_minute: u32,
+) -> Response {
+    if per_minute == 0 {
+        return next.run(req).await;
+    }
+
+    let limit = RateLimit { per_minute };
+    let bucket = format!("{}:{}", group, client_key(state.config.trust_proxy, &req));
+
+    let decision = match state.rate_limiter.take(&bucket, &limit).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            tracing::warn!("Rate limiter unavailable, letting request through: {}", e);
+            return next.run(req).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        AppError::TooManyRequests.into_response()
+    };
+
+    insert_headers(response.headers_mut(), &decision);
+
+    response
+}
+
+/// API keys and users get a bucket each, whatever address they call from.
+/// Anonymous callers are told apart by IP; with `TRUST_PROXY` set, the last
+/// `X-Forwarded-For` entry is used, as added by our own reverse proxy.
+fn client_key(trust_proxy: bool, req: &Request<Body>) -> String {
+    if let Some(key) = req.extensions().get::<ApiKey>() {
+        return format!("key:{}", key.id);
+    }
+
+    if let Some(user) = req.extensions().get::<User>() {
+        return format!("user:{}", user.id);
+    }
+
+    let forwarded = trust_proxy
+        .then(|| {
+            req.headers()
+                .get("X-Forwarded-For")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+        })
+        .flatten();
+
+    let ip = forwarded.or_else(|| {
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    });
+
+    match ip {
+        Some(ip) => format!("ip:{}", ip),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+fn insert_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert("RateLimit-Limit", HeaderValue::from(decision.limit));
+    headers.insert("RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    headers.insert("RateLimit-Reset", HeaderValue::from(decision.reset_after));
+
+    if let Ok(policy) = HeaderValue::from_str(&format!("{};w=60", decision.limit)) {
+        headers.insert("RateLimit-Policy", policy);
+    }
+
+    if !decision.allowed {
+        headers.insert(RETRY_AFTER, HeaderValue::from(decision.retry_after));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(forwarded_for: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/api/word/bank");
+        if let Some(value) = forwarded_for {
+            builder = builder.header("X-Forwarded-For", value);
+        }
+
+        let mut req = builder.body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+        req
+    }
+
+    #[test]
+    fn client_key_uses_peer_address_without_trust_proxy() {
+        let req = request(Some("203.0.113.7"));
+
+        assert_eq!(client_key(false, &req), "ip:10.0.0.1");
+    }
+
+    #[test]
+    fn client_key_uses_last_forwarded_entry_with_trust_proxy() {
+        // Earlier entries are whatever the client sent; the last one is
+        // added by our proxy.
+        let req = request(Some("198.51.100.1, 203.0.113.7"));
+
+        assert_eq!(client_key(true, &req), "ip:203.0.113.7");
+    }
+
+    #[test]
+    fn client_key_falls_back_to_peer_address() {
+        assert_eq!(client_key(true, &request(None)), "ip:10.0.0.1");
+        assert_eq!(client_key(true, &request(Some("not-an-ip"))), "ip:10.0.0.1");
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        assert_eq!(client_key(false, &req), "ip:unknown");
+    }
+}
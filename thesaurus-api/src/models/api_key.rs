@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// `read` covers lookups, `lists` covers managing word lists.
+pub const API_KEY_SCOPES: [&str; 2] = ["read", "lists"];
+
+/// A personal API key. The key itself is only shown once when it is
+/// created; `prefix` is kept so users can tell their keys apart.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateApiKeySchema {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_scope_matches_whole_scopes() {
+        let key = ApiKey {
+            id: uuid::Uuid::new_v4(),
+            user_id: uuid::Uuid::new_v4(),
+            name: "test".to_string(),
+            prefix: "fsk_0123abcd".to_string(),
+            scopes: vec!["read".to_string()],
+            last_used_at: None,
+            revoked_at: None,
+            created_at: chrono::Utc::now(),
+        };
+
+        assert!(key.has_scope("read"));
+        assert!(!key.has_scope("lists"));
+        assert!(!key.has_scope("rea"));
+        assert!(!key.has_scope(""));
+    }
+}
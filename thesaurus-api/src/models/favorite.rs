@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// A favorited word. Removed favorites are kept as tombstones (`deleted_at`
+/// set) so other devices learn about the removal when they sync.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Favorite {
+    pub id: uuid::Uuid,
+    pub word: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AddFavoriteSchema {
+    #[validate(length(min = 1, max = 255, message = "Word must be 1-255 characters"))]
+    pub word: String,
+
+    /// When the word was favorited on the client, for offline changes.
+    pub added_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveFavoriteQuery {
+    /// When the word was unfavorited on the client, for offline changes.
+    pub removed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FavoritesQuery {
+    /// Only return favorites changed after this time, including removals.
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+}
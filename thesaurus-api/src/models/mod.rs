@@ -1,2 +1,11 @@
+pub mod api_key;
+pub mod favorite;
+pub mod history;
+pub mod oidc;
+pub mod organization;
+pub mod proposal;
+pub mod session;
+pub mod suggestion;
 pub mod user;
 pub mod word;
+pub mod word_list;
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// A group of users who see each other's relation suggestions.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Organization {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub member_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateOrganizationSchema {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1-255 characters"))]
+    pub name: String,
+}
+
+/// `null` takes the user out of their organisation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUserOrganizationSchema {
+    pub organization_id: Option<uuid::Uuid>,
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// Fields of a `words` document that proposals can edit.
+pub const PROPOSAL_FIELDS: [&str; 7] = [
+    "definitions",
+    "examples",
+    "synonyms",
+    "antonyms",
+    "broader_terms",
+    "narrower_terms",
+    "related_terms",
+];
+
+/// `add` appends `value`, `remove` drops it and `replace` swaps
+/// `old_value` for `value`.
+pub const PROPOSAL_ACTIONS: [&str; 3] = ["add", "remove", "replace"];
+
+/// A proposed correction to a word. Goes from `pending` to `approved`,
+/// `rejected` or `merged` (a duplicate of `merged_into`). Approved proposals
+/// get `applied_at` once they have been written to the search index.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Proposal {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub word: String,
+    pub language: String,
+    pub field: String,
+    pub action: String,
+    pub value: String,
+    pub old_value: Option<String>,
+    pub reason: Option<String>,
+    pub status: String,
+    pub merged_into: Option<uuid::Uuid>,
+    pub reviewed_by: Option<uuid::Uuid>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub applied_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub apply_error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One entry of the moderation audit trail for a proposal or suggestion.
+/// `actor_id` is empty for actions taken by the server itself.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ModerationEvent {
+    pub id: uuid::Uuid,
+    pub subject_type: String,
+    pub subject_id: uuid::Uuid,
+    pub actor_id: Option<uuid::Uuid>,
+    pub action: String,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateProposalSchema {
+    #[validate(length(min = 1, max = 255, message = "Word must be 1-255 characters"))]
+    pub word: String,
+
+    #[validate(length(min = 1, max = 16, message = "Language must be 1-16 characters"))]
+    pub lang: Option<String>,
+
+    pub field: String,
+
+    pub action: String,
+
+    #[validate(length(min = 1, max = 2000, message = "Value must be 1-2000 characters"))]
+    pub value: String,
+
+    #[validate(length(max = 2000, message = "Old value must be at most 2000 characters"))]
+    pub old_value: Option<String>,
+
+    #[validate(length(max = 2000, message = "Reason must be at most 2000 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationQuery {
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default = "default_offset")]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_status() -> String {
+    "pending".to_string()
+}
+
+fn default_offset() -> i64 {
+    0
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ReviewSchema {
+    #[validate(length(max = 2000, message = "Note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MergeProposalSchema {
+    /// The proposal this one duplicates.
+    pub into: uuid::Uuid,
+
+    #[validate(length(max = 2000, message = "Note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One refresh token in a session. Refreshing marks the row as rotated and
+/// adds a new one with the same `family_id`.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Session {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub family_id: uuid::Uuid,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The session behind the current access token, added next to the `User`
+/// extension by the auth middleware.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionId(pub uuid::Uuid);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RefreshTokenSchema {
+    pub refresh_token: Option<String>,
+}
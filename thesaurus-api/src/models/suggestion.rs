@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// A user-contributed relation between `word` and `related_word`.
+///
+/// `provenance` says where it came from relative to the viewer: `user` for
+/// their own suggestions, `organization` for ones made by members of their
+/// organisation and `community` for approved suggestions from anyone else.
+/// Relations that are not suggestions come from WordNet.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Suggestion {
+    pub id: uuid::Uuid,
+    pub word: String,
+    pub related_word: String,
+    pub relation: String,
+    pub language: String,
+    pub status: String,
+    pub provenance: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateSuggestionSchema {
+    #[validate(length(min = 1, max = 255, message = "Word must be 1-255 characters"))]
+    pub word: String,
+
+    #[validate(length(min = 1, max = 255, message = "Related word must be 1-255 characters"))]
+    pub related_word: String,
+
+    /// One of `synonyms`, `antonyms`, `broader_terms`, `narrower_terms` or
+    /// `related_terms`.
+    pub relation: String,
+
+    #[validate(length(min = 1, max = 16, message = "Language must be 1-16 characters"))]
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestionSettingsSchema {
+    pub show_community: bool,
+}
@@ -2,17 +2,37 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: uuid::Uuid,
     pub name: String,
     pub email: String,
+    /// Empty for accounts that only sign in through an identity provider.
     #[serde(skip_serializing)]
-    pub password: String,
+    pub password: Option<String>,
+    pub role: String,
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub history_paused: bool,
+    pub organization_id: Option<uuid::Uuid>,
+    pub show_community_suggestions: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Roles a user can have. Editors can work the moderation queue, admins can
+/// also change other users' roles.
+pub const ROLES: [&str; 3] = ["user", "editor", "admin"];
+
+impl User {
+    pub fn is_admin(&self) -> bool {
+        self.role == "admin"
+    }
+
+    pub fn can_moderate(&self) -> bool {
+        matches!(self.role.as_str(), "editor" | "admin")
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct RegisterUserSchema {
     #[validate(length(min = 2, message = "Name must be at least 2 characters"))]
@@ -33,18 +53,47 @@ pub struct LoginUserSchema {
     pub password: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ForgotPasswordSchema {
+    #[validate(email(message = "Email must be valid"))]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ResetPasswordSchema {
+    pub token: String,
+
+    #[validate(length(min = 6, message = "Password must be at least 6 characters"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmailSchema {
+    pub token: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UserResponse {
     pub id: String,
     pub name: String,
     pub email: String,
+    pub role: String,
+    pub email_verified: bool,
+    pub organization_id: Option<uuid::Uuid>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRoleSchema {
+    pub role: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: String,
+    /// The session the token was issued for; see `user_sessions.family_id`.
+    pub sid: String,
     pub iat: usize,
     pub exp: usize,
 }
@@ -55,6 +104,9 @@ impl From<User> for UserResponse {
             id: user.id.to_string(),
             name: user.name,
             email: user.email,
+            role: user.role,
+            email_verified: user.email_verified_at.is_some(),
+            organization_id: user.organization_id,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
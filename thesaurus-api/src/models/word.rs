@@ -1,19 +1,186 @@
+use super::suggestion::Suggestion;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Word {
     pub id: String,
     pub word: String,
+    pub language: String,
+    pub lexicon: String,
     pub definitions: Vec<String>,
     pub pos: Vec<String>, // Parts of speech
     pub synonyms: Vec<String>,
     pub antonyms: Vec<String>,
-    pub broader_terms: Vec<String>,  // Hypernyms
-    pub narrower_terms: Vec<String>, // Hyponyms
-    pub related_terms: Vec<String>,  // Other relations
+    pub broader_terms: Vec<String>,   // Hypernyms
+    pub narrower_terms: Vec<String>,  // Hyponyms
+    pub related_terms: Vec<String>,   // Other relations
+    pub relations: Vec<WordRelation>, // Every relation with its WordNet relType
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub suggestions: Vec<Suggestion>, // User-contributed relations merged in above
+    #[serde(default, skip_serializing)]
+    pub applied_proposals: Vec<String>, // Approved edits written into this document
+}
+
+/// A related lemma with its WordNet `relType`. `bucket` is the coarse
+/// relation list the importer folded it into.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct WordRelation {
+    pub rel_type: String,
+    pub bucket: String,
+    pub word: String,
+}
+
+impl WordRelation {
+    pub fn relation_type(&self) -> Option<RelationType> {
+        RelationType::from_name(&self.bucket)
+    }
+}
+
+/// Just the typed relations of a `words` document.
+#[derive(Debug, Deserialize)]
+pub struct WordRelations {
+    pub relations: Vec<WordRelation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Sense {
+    pub id: String,
+    pub word: String,
+    pub language: String,
+    pub lexicon: String,
+    pub word_id: String,
+    pub sense_id: String,
+    pub synset_id: String,
+    pub ili: Option<String>,
+    pub sense_number: usize,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+    pub broader_terms: Vec<String>,
+    pub narrower_terms: Vec<String>,
+    pub related_terms: Vec<String>,
+    pub relations: Vec<WordRelation>,
     pub examples: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SenseGroup {
+    pub pos: String,
+    pub senses: Vec<Sense>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Concept {
+    pub id: String,
+    pub ili: String,
+    pub languages: Vec<String>,
+    pub synsets: Vec<ConceptSynset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConceptSynset {
+    pub synset_id: String,
+    pub language: String,
+    pub lexicon: String,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+}
+
+/// One sense of the source word and its equivalents in the target language.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Translation {
+    pub sense_id: String,
+    pub synset_id: String,
+    pub ili: String,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub equivalents: Vec<ConceptSynset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Synset {
+    pub id: String,
+    pub synset_id: String,
+    pub language: String,
+    pub lexicon: String,
+    pub ili: Option<String>,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+    pub relations: Vec<SynsetRelation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SynsetRelation {
+    pub rel_type: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub synset_id: String,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub relation: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationGraph {
+    pub word: String,
+    pub relation: Option<String>,
+    pub depth: usize,
+    pub roots: Vec<String>,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimilaritySense {
+    pub synset_id: String,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+}
+
+/// Similarity measures for one sense of each word.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimilarityPair {
+    pub a: SimilaritySense,
+    pub b: SimilaritySense,
+    pub lowest_common_hypernym: Option<String>,
+    pub path: f64,
+    pub wu_palmer: f64,
+    pub leacock_chodorow: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarityResponse {
+    pub a: String,
+    pub b: String,
+    pub pairs: Vec<SimilarityPair>,
+    pub best: Option<SimilarityPair>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelationTypeInfo {
+    pub rel_type: String,
+    pub coarse: String,
+    pub count: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResponse {
     pub hits: Vec<Word>,
@@ -24,15 +191,31 @@ pub struct SearchResponse {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchFilters {
+    pub lang: String,
     pub pos: Option<String>,
     pub exact_match: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RelationResponse {
+/// A lemma offered for a misspelt word. `distance` is measured in edits,
+/// where typical typing slips count as half an edit.
+#[derive(Debug, Clone, Serialize)]
+pub struct Correction {
     pub word: String,
-    pub relation: String,
-    pub related_words: Vec<String>,
+    pub distance: f32,
+}
+
+/// Looks up several words at once. `relations` narrows each word down to
+/// the named relation lists, as returned by [`RelationType::as_str`].
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchWordsSchema {
+    #[validate(length(
+        min = 1,
+        max = 200,
+        message = "Between 1 and 200 words can be looked up at once"
+    ))]
+    pub words: Vec<String>,
+    pub relations: Option<Vec<String>>,
+    pub lang: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
@@ -45,6 +228,14 @@ pub enum RelationType {
 }
 
 impl RelationType {
+    pub const ALL: [RelationType; 5] = [
+        RelationType::Synonym,
+        RelationType::Antonym,
+        RelationType::BroaderTerm,
+        RelationType::NarrowerTerm,
+        RelationType::RelatedTerm,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             RelationType::Synonym => "synonyms",
@@ -55,6 +246,18 @@ impl RelationType {
         }
     }
 
+    /// Parses the names returned by [`RelationType::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "synonyms" => Some(RelationType::Synonym),
+            "antonyms" => Some(RelationType::Antonym),
+            "broader_terms" => Some(RelationType::BroaderTerm),
+            "narrower_terms" => Some(RelationType::NarrowerTerm),
+            "related_terms" => Some(RelationType::RelatedTerm),
+            _ => None,
+        }
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             RelationType::Synonym => "Synonyms",
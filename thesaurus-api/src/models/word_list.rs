@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WordList {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub share_token: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WordListSummary {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub share_token: Option<String>,
+    pub entry_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WordListEntry {
+    pub id: uuid::Uuid,
+    pub word: String,
+    pub note: Option<String>,
+    pub position: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A list together with its entries in display order.
+#[derive(Debug, Serialize)]
+pub struct WordListWithEntries {
+    #[serde(flatten)]
+    pub list: WordList,
+    pub entries: Vec<WordListEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateWordListSchema {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1-255 characters"))]
+    pub name: String,
+
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateWordListSchema {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1-255 characters"))]
+    pub name: Option<String>,
+
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AddEntrySchema {
+    #[validate(length(min = 1, max = 255, message = "Word must be 1-255 characters"))]
+    pub word: String,
+
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateEntrySchema {
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderEntriesSchema {
+    /// Every entry id of the list, in the new order.
+    pub entry_ids: Vec<uuid::Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
@@ -0,0 +1,81 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        api_key::{CreateApiKeySchema, API_KEY_SCOPES},
+        user::User,
+    },
+    services::api_keys::{create_api_key, list_api_keys, revoke_api_key},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let keys = list_api_keys(&state.db, &user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "api_keys": keys,
+    })))
+}
+
+/// Creates a key. The response is the only time the full key is shown.
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(mut payload): Json<CreateApiKeySchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    if let Some(scope) = payload
+        .scopes
+        .iter()
+        .find(|scope| !API_KEY_SCOPES.contains(&scope.as_str()))
+    {
+        return Err(AppError::ValidationError(format!(
+            "Unknown scope '{}', expected one of: {}",
+            scope,
+            API_KEY_SCOPES.join(", ")
+        )));
+    }
+
+    payload.scopes.sort();
+    payload.scopes.dedup();
+
+    let (api_key, key) =
+        create_api_key(&state.db, &user.id, payload.name.trim(), &payload.scopes).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "api_key": api_key,
+            "key": key,
+        })),
+    ))
+}
+
+pub async fn revoke(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(key_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let api_key = revoke_api_key(&state.db, &user.id, &key_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "api_key": api_key,
+    })))
+}
@@ -1,52 +1,141 @@
 use crate::{
+    config::Config,
     db::AppState,
     error::AppError,
-    models::user::{LoginUserSchema, RegisterUserSchema, UserResponse},
-    services::auth::{create_user, generate_token, login_user, verify_token},
+    models::{
+        session::{RefreshTokenSchema, SessionId},
+        user::{
+            ForgotPasswordSchema, LoginUserSchema, RegisterUserSchema, ResetPasswordSchema, User,
+            UserResponse, VerifyEmailSchema,
+        },
+    },
+    services::{
+        account::{request_password_reset, reset_password, send_verification_email, verify_email},
+        auth::{create_user, generate_token, login_user},
+        sessions::{create_session, revoke_all_sessions, revoke_session, rotate_session},
+    },
 };
 use axum::{
+    body::Bytes,
     extract::State,
-    http::{header, HeaderMap},
+    http::{header, HeaderMap, HeaderValue},
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use serde_json::json;
 use validator::Validate;
 
-pub async fn register(
-    State(state): State<AppState>,
-    Json(payload): Json<RegisterUserSchema>,
-) -> Result<impl IntoResponse, AppError> {
-    payload
-        .validate()
-        .map_err(|e| AppError::ValidationError(e.to_string()))?;
-
-    let user = create_user(&state.db, &payload.name, &payload.email, &payload.password).await?;
-
-    let token = generate_token(&state.config, &user.id)?;
+const REFRESH_COOKIE: &str = "refresh_token";
 
+/// Sets the access token cookie and the refresh token cookie, which is only
+/// sent back to `/auth`.
+pub fn session_cookies(config: &Config, token: &str, refresh_token: &str) -> HeaderMap {
     let mut headers = HeaderMap::new();
-    headers.insert(
+    headers.append(
         header::SET_COOKIE,
         format!(
             "token={}; Max-Age={}; Path=/; HttpOnly; SameSite=Strict",
             token,
-            state.config.jwt_maxage * 60
+            config.jwt_maxage * 60
+        )
+        .parse()
+        .unwrap(),
+    );
+    headers.append(
+        header::SET_COOKIE,
+        format!(
+            "{}={}; Max-Age={}; Path=/auth; HttpOnly; SameSite=Strict",
+            REFRESH_COOKIE,
+            refresh_token,
+            config.refresh_token_days * 24 * 60 * 60
         )
         .parse()
         .unwrap(),
     );
+    headers
+}
+
+fn cleared_cookies() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_static("token=; Max-Age=0; Path=/; HttpOnly; SameSite=Strict"),
+    );
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_static(
+            "refresh_token=; Max-Age=0; Path=/auth; HttpOnly; SameSite=Strict",
+        ),
+    );
+    headers
+}
+
+/// The refresh token from a JSON body, falling back to the cookie.
+fn refresh_token_from(headers: &HeaderMap, body: &Bytes) -> Option<String> {
+    let from_body = serde_json::from_slice::<RefreshTokenSchema>(body)
+        .unwrap_or_default()
+        .refresh_token;
+
+    from_body.or_else(|| cookie_value(headers, REFRESH_COOKIE))
+}
+
+/// The value of the cookie called `name`, if the request sent one.
+pub fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Starts a session for `user` and builds the login/register response.
+async fn start_session(state: &AppState, user: User) -> Result<impl IntoResponse, AppError> {
+    let (session, refresh_token) = create_session(&state.db, &state.config, &user.id).await?;
+    let token = generate_token(&state.config, &user.id, &session.family_id)?;
 
     Ok((
-        headers,
+        session_cookies(&state.config, &token, &refresh_token),
         Json(json!({
             "status": "success",
             "token": token,
+            "refresh_token": refresh_token,
             "user": UserResponse::from(user)
         })),
     ))
 }
 
+pub async fn register(
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterUserSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let user = create_user(&state.db, &payload.name, &payload.email, &payload.password).await?;
+
+    let (pool, mailer, config, new_user) = (
+        state.db.clone(),
+        state.mailer.clone(),
+        state.config.clone(),
+        user.clone(),
+    );
+    tokio::spawn(async move {
+        if let Err(e) = send_verification_email(&pool, mailer.as_ref(), &config, &new_user).await {
+            tracing::warn!(
+                "Failed to send verification email to {}: {}",
+                new_user.email,
+                e
+            );
+        }
+    });
+
+    start_session(&state, user).await
+}
+
 pub async fn login(
     State(state): State<AppState>,
     Json(payload): Json<LoginUserSchema>,
@@ -57,68 +146,174 @@ pub async fn login(
 
     let user = login_user(&state.db, &payload.email, &payload.password).await?;
 
-    let token = generate_token(&state.config, &user.id)?;
+    start_session(&state, user).await
+}
 
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        header::SET_COOKIE,
-        format!(
-            "token={}; Max-Age={}; Path=/; HttpOnly; SameSite=Strict",
-            token,
-            state.config.jwt_maxage * 60
-        )
-        .parse()
-        .unwrap(),
-    );
+/// Exchanges a refresh token, sent as `{"refresh_token": ...}` or in the
+/// `refresh_token` cookie, for a new access token and refresh token.
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let refresh_token = refresh_token_from(&headers, &body).ok_or(AppError::Unauthorized)?;
+
+    let (session, new_refresh_token) =
+        rotate_session(&state.db, &state.config, &refresh_token).await?;
+    let new_token = generate_token(&state.config, &session.user_id, &session.family_id)?;
 
     Ok((
-        headers,
+        session_cookies(&state.config, &new_token, &new_refresh_token),
         Json(json!({
             "status": "success",
-            "token": token,
-            "user": UserResponse::from(user)
+            "token": new_token,
+            "refresh_token": new_refresh_token,
         })),
     ))
 }
 
-pub async fn refresh_token(
+pub async fn logout(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    Extension(user): Extension<User>,
+    Extension(SessionId(session_id)): Extension<SessionId>,
 ) -> Result<impl IntoResponse, AppError> {
-    let token = headers
-        .get(header::AUTHORIZATION)
-        .and_then(|auth_header| auth_header.to_str().ok())
-        .and_then(|auth_value| {
-            if auth_value.starts_with("Bearer ") {
-                Some(auth_value[7..].to_owned())
-            } else {
-                None
-            }
-        })
-        .ok_or(AppError::Unauthorized)?;
-
-    let claims = verify_token(&state.config, &token)?;
-    let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized)?;
-
-    let new_token = generate_token(&state.config, &user_id)?;
-
-    let mut response_headers = HeaderMap::new();
-    response_headers.insert(
-        header::SET_COOKIE,
-        format!(
-            "token={}; Max-Age={}; Path=/; HttpOnly; SameSite=Strict",
-            new_token,
-            state.config.jwt_maxage * 60
+    revoke_session(&state.db, &user.id, &session_id).await?;
+
+    Ok((
+        cleared_cookies(),
+        Json(json!({
+            "status": "success",
+        })),
+    ))
+}
+
+pub async fn logout_all(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<impl IntoResponse, AppError> {
+    let revoked = revoke_all_sessions(&state.db, &user.id).await?;
+
+    Ok((
+        cleared_cookies(),
+        Json(json!({
+            "status": "success",
+            "revoked_sessions": revoked,
+        })),
+    ))
+}
+
+/// Always answers the same way; the reset email is sent in the background
+/// so response times do not reveal whether the account exists.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(payload): Json<ForgotPasswordSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    tokio::spawn(async move {
+        if let Err(e) = request_password_reset(
+            &state.db,
+            state.mailer.as_ref(),
+            &state.config,
+            &payload.email,
         )
-        .parse()
-        .unwrap(),
-    );
+        .await
+        {
+            tracing::warn!("Failed to send password reset email: {}", e);
+        }
+    });
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "If an account exists for that email, a reset link is on its way",
+    })))
+}
+
+pub async fn reset_password_with_token(
+    State(state): State<AppState>,
+    Json(payload): Json<ResetPasswordSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    reset_password(&state.db, &payload.token, &payload.password).await?;
 
     Ok((
-        response_headers,
+        cleared_cookies(),
         Json(json!({
             "status": "success",
-            "token": new_token
         })),
     ))
 }
+
+pub async fn verify_email_with_token(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyEmailSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = verify_email(&state.db, &payload.token).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "user": UserResponse::from(user),
+    })))
+}
+
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    send_verification_email(&state.db, state.mailer.as_ref(), &state.config, &user).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cookies(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn refresh_token_prefers_the_body() {
+        let headers = cookies("refresh_token=from-cookie");
+        let body = Bytes::from_static(br#"{"refresh_token":"from-body"}"#);
+
+        assert_eq!(
+            refresh_token_from(&headers, &body).as_deref(),
+            Some("from-body")
+        );
+    }
+
+    #[test]
+    fn refresh_token_falls_back_to_the_cookie() {
+        let headers = cookies("token=access; refresh_token=from-cookie");
+
+        for body in [&b""[..], b"{}", b"not json", br#"{"refresh_token":null}"#] {
+            assert_eq!(
+                refresh_token_from(&headers, &Bytes::copy_from_slice(body)).as_deref(),
+                Some("from-cookie")
+            );
+        }
+        assert_eq!(refresh_token_from(&HeaderMap::new(), &Bytes::new()), None);
+    }
+
+    #[test]
+    fn cookie_value_matches_whole_names() {
+        let headers = cookies("xrefresh_token=a; refresh_token=b=c");
+
+        assert_eq!(
+            cookie_value(&headers, REFRESH_COOKIE).as_deref(),
+            Some("b=c")
+        );
+        assert_eq!(cookie_value(&headers, "token"), None);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        favorite::{AddFavoriteSchema, FavoritesQuery, RemoveFavoriteQuery},
+        user::User,
+    },
+    services::favorites::{add_favorite, get_favorite, list_favorites, remove_favorite},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<FavoritesQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let (favorites, server_time) = list_favorites(&state.db, &user.id, query.since).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "favorites": favorites,
+        "server_time": server_time,
+    })))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(word): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let favorite = get_favorite(&state.db, &user.id, &word).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "favorite": favorite,
+    })))
+}
+
+pub async fn add(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<AddFavoriteSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let word = payload.word.trim();
+    if word.is_empty() {
+        return Err(AppError::ValidationError(
+            "Word must not be blank".to_string(),
+        ));
+    }
+
+    let favorite = add_favorite(&state.db, &user.id, word, payload.added_at).await?;
+
+    // A newer offline removal wins, and nothing was created.
+    let status = if favorite.deleted_at.is_none() {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((
+        status,
+        Json(json!({
+            "status": "success",
+            "favorite": favorite,
+        })),
+    ))
+}
+
+pub async fn remove(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(word): Path<String>,
+    Query(query): Query<RemoveFavoriteQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let favorite = remove_favorite(&state.db, &user.id, &word, query.removed_at).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "favorite": favorite,
+    })))
+}
@@ -1,4 +1,4 @@
-use crate::db::AppState;
+use crate::{db::AppState, services::search::word_cache};
 use axum::extract::State;
 use axum::{http::StatusCode, Json};
 use serde_json::json;
@@ -31,7 +31,8 @@ pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<se
             "services": {
                 "meili": meili_status,
                 "postgres": postgres_status,
-            }
+            },
+            "word_cache": word_cache().map(|cache| cache.stats()),
         })),
     )
 }
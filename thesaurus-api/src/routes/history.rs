@@ -0,0 +1,73 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        history::{HistoryQuery, HistorySettingsSchema},
+        user::User,
+    },
+    services::history::{clear_history, delete_history_entry, list_history, set_history_paused},
+};
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+const MAX_HISTORY_PAGE: i64 = 500;
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let limit = query.limit.clamp(1, MAX_HISTORY_PAGE);
+    let offset = query.offset.max(0);
+
+    let entries = list_history(&state.db, &user.id, offset, limit).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "paused": user.history_paused,
+        "history": entries,
+        "offset": offset,
+        "limit": limit,
+    })))
+}
+
+pub async fn delete_entry(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    delete_history_entry(&state.db, &user.id, &entry_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+    })))
+}
+
+pub async fn clear(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let deleted = clear_history(&state.db, &user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "deleted": deleted,
+    })))
+}
+
+pub async fn update_settings(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<HistorySettingsSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    set_history_paused(&state.db, &user.id, payload.paused).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "paused": payload.paused,
+    })))
+}
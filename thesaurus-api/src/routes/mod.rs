@@ -1,3 +1,12 @@
+pub mod api_keys;
 pub mod auth;
+pub mod favorites;
 pub mod health;
+pub mod history;
+pub mod moderation;
+pub mod oidc;
+pub mod organizations;
+pub mod proposals;
+pub mod suggestions;
 pub mod thesaurus;
+pub mod word_lists;
@@ -0,0 +1,208 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        proposal::{MergeProposalSchema, ModerationQuery, ReviewSchema},
+        user::{UpdateRoleSchema, User, UserResponse, ROLES},
+    },
+    services::moderation::{
+        get_proposal, list_events, list_proposals, list_suggestions_for_review, merge_proposal,
+        review_proposal, review_suggestion, set_user_role,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+const MAX_QUEUE_PAGE: i64 = 200;
+
+pub async fn list_proposal_queue(
+    State(state): State<AppState>,
+    Query(query): Query<ModerationQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let limit = query.limit.clamp(1, MAX_QUEUE_PAGE);
+    let offset = query.offset.max(0);
+
+    let proposals = list_proposals(&state.db, &query.status, offset, limit).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "proposals": proposals,
+        "offset": offset,
+        "limit": limit,
+    })))
+}
+
+pub async fn get_proposal_with_events(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proposal = get_proposal(&state.db, &proposal_id).await?;
+    let events = list_events(&state.db, "proposal", &proposal_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "proposal": proposal,
+        "events": events,
+    })))
+}
+
+pub async fn approve_proposal(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(proposal_id): Path<Uuid>,
+    Json(payload): Json<ReviewSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    review(&state, &user, &proposal_id, "approved", payload).await
+}
+
+pub async fn reject_proposal(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(proposal_id): Path<Uuid>,
+    Json(payload): Json<ReviewSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    review(&state, &user, &proposal_id, "rejected", payload).await
+}
+
+async fn review(
+    state: &AppState,
+    user: &User,
+    proposal_id: &Uuid,
+    status: &str,
+    payload: ReviewSchema,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let proposal = review_proposal(
+        &state.db,
+        &user.id,
+        proposal_id,
+        status,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "proposal": proposal,
+    })))
+}
+
+pub async fn merge(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(proposal_id): Path<Uuid>,
+    Json(payload): Json<MergeProposalSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let proposal = merge_proposal(
+        &state.db,
+        &user.id,
+        &proposal_id,
+        &payload.into,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "proposal": proposal,
+    })))
+}
+
+pub async fn list_suggestion_queue(
+    State(state): State<AppState>,
+    Query(query): Query<ModerationQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let limit = query.limit.clamp(1, MAX_QUEUE_PAGE);
+    let offset = query.offset.max(0);
+
+    let suggestions = list_suggestions_for_review(&state.db, &query.status, offset, limit).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "suggestions": suggestions,
+        "offset": offset,
+        "limit": limit,
+    })))
+}
+
+pub async fn approve_suggestion(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(suggestion_id): Path<Uuid>,
+    Json(payload): Json<ReviewSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    review_suggestion_as(&state, &user, &suggestion_id, "approved", payload).await
+}
+
+pub async fn reject_suggestion(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(suggestion_id): Path<Uuid>,
+    Json(payload): Json<ReviewSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    review_suggestion_as(&state, &user, &suggestion_id, "rejected", payload).await
+}
+
+async fn review_suggestion_as(
+    state: &AppState,
+    user: &User,
+    suggestion_id: &Uuid,
+    status: &str,
+    payload: ReviewSchema,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let suggestion = review_suggestion(
+        &state.db,
+        &user.id,
+        suggestion_id,
+        status,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "suggestion": suggestion,
+    })))
+}
+
+pub async fn update_role(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateRoleSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !ROLES.contains(&payload.role.as_str()) {
+        return Err(AppError::ValidationError(format!(
+            "Role must be one of: {}",
+            ROLES.join(", ")
+        )));
+    }
+    if user_id == user.id {
+        return Err(AppError::BadRequest(
+            "Admins cannot change their own role".to_string(),
+        ));
+    }
+
+    let updated = set_user_role(&state.db, &user.id, &user_id, &payload.role).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "user": UserResponse::from(updated),
+    })))
+}
@@ -0,0 +1,190 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::oidc::{OidcCallbackQuery, OidcLoginQuery},
+    routes::auth::{cookie_value, session_cookies},
+    services::{
+        auth::generate_token,
+        oidc::{
+            find_or_create_user, save_login_state, take_login_state, OidcClient,
+            LOGIN_STATE_TTL_MINUTES,
+        },
+        sessions::create_session,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Redirect},
+};
+use std::sync::Arc;
+
+/// Ties a login to the browser that started it, so a callback URL can not
+/// be replayed in someone else's browser.
+const STATE_COOKIE: &str = "oidc_state";
+
+fn oidc_client(state: &AppState) -> Result<&Arc<OidcClient>, AppError> {
+    state
+        .oidc
+        .as_ref()
+        .ok_or_else(|| AppError::NotFound("OIDC login is not configured".to_string()))
+}
+
+fn state_cookie(state: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        format!(
+            "{}={}; Max-Age={}; Path=/auth/oidc; HttpOnly; SameSite=Lax",
+            STATE_COOKIE,
+            state,
+            LOGIN_STATE_TTL_MINUTES * 60
+        )
+        .parse()
+        .unwrap(),
+    );
+    headers
+}
+
+fn cleared_state_cookie() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_static("oidc_state=; Max-Age=0; Path=/auth/oidc; HttpOnly; SameSite=Lax"),
+    );
+    headers
+}
+
+/// Only redirects back into the app, never to another site. Browsers read
+/// `/\host` as `//host` and skip tabs and newlines, so targets containing
+/// backslashes or control characters are refused outright.
+fn safe_redirect(app_url: &str, redirect_to: Option<&str>) -> String {
+    let app_url = app_url.trim_end_matches('/');
+    let redirect_to =
+        redirect_to.filter(|target| !target.chars().any(|c| c == '\\' || c.is_control()));
+
+    match redirect_to {
+        Some(path) if path.starts_with('/') && !path.starts_with("//") => {
+            format!("{}{}", app_url, path)
+        }
+        Some(url)
+            if url == app_url
+                || url
+                    .strip_prefix(app_url)
+                    .is_some_and(|rest| rest.starts_with('/')) =>
+        {
+            url.to_string()
+        }
+        _ => app_url.to_string(),
+    }
+}
+
+/// Redirects the browser to the identity provider, remembering the login
+/// state in a cookie.
+pub async fn login(
+    State(state): State<AppState>,
+    Query(query): Query<OidcLoginQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let oidc = oidc_client(&state)?;
+
+    let redirect_to = safe_redirect(&state.config.app_url, query.redirect_to.as_deref());
+    let login = save_login_state(&state.db, &redirect_to).await?;
+    let url = oidc.authorization_url(&login).await?;
+
+    Ok((state_cookie(&login.state), Redirect::to(&url)))
+}
+
+/// Finishes the login, sets the session cookies and sends the browser back
+/// to the app, which can pick up an access token from `/auth/refresh`.
+pub async fn callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let oidc = oidc_client(&state)?;
+
+    if cookie_value(&headers, STATE_COOKIE).as_deref() != Some(query.state.as_str()) {
+        return Err(AppError::BadRequest(
+            "This sign-in was started in another browser".to_string(),
+        ));
+    }
+
+    let login = take_login_state(&state.db, &query.state).await?;
+
+    if let Some(error) = query.error {
+        return Err(AppError::BadRequest(format!(
+            "Sign-in failed: {}",
+            query.error_description.unwrap_or(error)
+        )));
+    }
+
+    let code = query
+        .code
+        .ok_or_else(|| AppError::BadRequest("Missing authorization code".to_string()))?;
+
+    let claims = oidc.exchange_code(&code, &login).await?;
+    let user = find_or_create_user(&state.db, oidc.issuer(), &claims).await?;
+
+    let (session, refresh_token) = create_session(&state.db, &state.config, &user.id).await?;
+    let token = generate_token(&state.config, &user.id, &session.family_id)?;
+
+    let redirect_to = login
+        .redirect_to
+        .unwrap_or_else(|| state.config.app_url.clone());
+
+    Ok((
+        cleared_state_cookie(),
+        session_cookies(&state.config, &token, &refresh_token),
+        Redirect::to(&redirect_to),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP_URL: &str = "https://app.example.com/";
+
+    #[test]
+    fn safe_redirect_accepts_paths_and_app_urls() {
+        assert_eq!(
+            safe_redirect(APP_URL, Some("/lists?tab=mine")),
+            "https://app.example.com/lists?tab=mine"
+        );
+        assert_eq!(
+            safe_redirect(APP_URL, Some("https://app.example.com/favorites")),
+            "https://app.example.com/favorites"
+        );
+        assert_eq!(safe_redirect(APP_URL, None), "https://app.example.com");
+    }
+
+    #[test]
+    fn safe_redirect_rejects_other_sites() {
+        for target in [
+            "https://evil.example.com/",
+            "https://app.example.com.evil.example.com/",
+            "//evil.example.com/path",
+            "/\\evil.example.com",
+            "\\evil.example.com",
+            "/\t/evil.example.com",
+            "javascript:alert(1)",
+        ] {
+            assert_eq!(
+                safe_redirect(APP_URL, Some(target)),
+                "https://app.example.com",
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn state_cookie_is_http_only_and_lax() {
+        let headers = state_cookie("abc");
+        let cookie = headers.get(header::SET_COOKIE).unwrap().to_str().unwrap();
+
+        assert!(cookie.starts_with("oidc_state=abc;"));
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.contains("SameSite=Lax"));
+    }
+}
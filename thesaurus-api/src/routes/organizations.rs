@@ -0,0 +1,70 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        organization::{CreateOrganizationSchema, UpdateUserOrganizationSchema},
+        user::UserResponse,
+    },
+    services::organizations::{
+        create_organization, delete_organization, list_organizations, set_user_organization,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn list(State(state): State<AppState>) -> Result<Json<serde_json::Value>, AppError> {
+    let organizations = list_organizations(&state.db).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "organizations": organizations,
+    })))
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateOrganizationSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let organization = create_organization(&state.db, payload.name.trim()).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "organization": organization,
+        })),
+    ))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    delete_organization(&state.db, &organization_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn update_membership(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateUserOrganizationSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = set_user_organization(&state.db, &user_id, payload.organization_id.as_ref()).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "user": UserResponse::from(user),
+    })))
+}
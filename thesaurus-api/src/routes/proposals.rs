@@ -0,0 +1,91 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        proposal::{CreateProposalSchema, PROPOSAL_ACTIONS, PROPOSAL_FIELDS},
+        user::User,
+    },
+    services::{
+        moderation::{create_proposal, list_user_proposals},
+        search::get_word_by_exact_match,
+    },
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proposals = list_user_proposals(&state.db, &user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "proposals": proposals,
+    })))
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<CreateProposalSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    if !PROPOSAL_FIELDS.contains(&payload.field.as_str()) {
+        return Err(AppError::ValidationError(format!(
+            "Field must be one of: {}",
+            PROPOSAL_FIELDS.join(", ")
+        )));
+    }
+    if !PROPOSAL_ACTIONS.contains(&payload.action.as_str()) {
+        return Err(AppError::ValidationError(format!(
+            "Action must be one of: {}",
+            PROPOSAL_ACTIONS.join(", ")
+        )));
+    }
+    if payload.action == "replace" && payload.old_value.is_none() {
+        return Err(AppError::ValidationError(
+            "Replacing a value needs old_value".to_string(),
+        ));
+    }
+
+    let lang = payload
+        .lang
+        .clone()
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    if get_word_by_exact_match(&state.meili, &payload.word, &lang)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::NotFound(format!(
+            "Word '{}' not found",
+            payload.word
+        )));
+    }
+
+    let proposal = create_proposal(
+        &state.db,
+        &user.id,
+        &payload.word,
+        &lang,
+        &payload.field,
+        &payload.action,
+        payload.value.trim(),
+        payload.old_value.as_deref(),
+        payload.reason.as_deref(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "proposal": proposal,
+        })),
+    ))
+}
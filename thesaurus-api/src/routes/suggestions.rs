@@ -0,0 +1,108 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        suggestion::{CreateSuggestionSchema, SuggestionSettingsSchema},
+        user::User,
+        word::RelationType,
+    },
+    services::suggestions::{
+        add_suggestion, delete_suggestion, list_suggestions, set_show_community,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let suggestions = list_suggestions(&state.db, &user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "show_community": user.show_community_suggestions,
+        "suggestions": suggestions,
+    })))
+}
+
+pub async fn add(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<CreateSuggestionSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let relation = RelationType::from_name(&payload.relation).ok_or_else(|| {
+        AppError::ValidationError(format!("Unknown relation '{}'", payload.relation))
+    })?;
+
+    let word = payload.word.trim();
+    let related_word = payload.related_word.trim();
+    if word.is_empty() || related_word.is_empty() {
+        return Err(AppError::ValidationError(
+            "Words must not be blank".to_string(),
+        ));
+    }
+    if word.eq_ignore_ascii_case(related_word) {
+        return Err(AppError::ValidationError(
+            "A word cannot be related to itself".to_string(),
+        ));
+    }
+
+    let lang = payload
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    let suggestion = add_suggestion(
+        &state.db,
+        &user.id,
+        word,
+        related_word,
+        relation.as_str(),
+        &lang,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "suggestion": suggestion,
+        })),
+    ))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(suggestion_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    delete_suggestion(&state.db, &user.id, &suggestion_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+    })))
+}
+
+pub async fn update_settings(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<SuggestionSettingsSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    set_show_community(&state.db, &user.id, payload.show_community).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "show_community": payload.show_community,
+    })))
+}
@@ -1,17 +1,31 @@
 use crate::{
     db::AppState,
     error::AppError,
-    models::word::{RelationType, SearchFilters},
+    middleware::lemma::LemmaPath,
+    models::{
+        user::User,
+        word::{BatchWordsSchema, RelationType, SearchFilters, Word},
+    },
+    services::autocomplete,
+    services::graph::{build_relation_graph, default_depth, MAX_GRAPH_DEPTH},
+    services::history::record_lookup,
+    services::morphy::{resolve, Resolution},
     services::search::{
-        get_all_word_relations, get_relations, get_word_by_exact_match, get_word_definition,
-        get_word_examples, search_words,
+        get_all_word_relations, get_relation_types, get_relations, get_typed_relations,
+        get_word_by_exact_match, get_word_definition, get_word_examples, get_word_senses,
+        get_words_by_exact_match, retain_relations, search_words,
     },
+    services::similarity::compare_words,
+    services::spellcheck::{corrections, word_not_found, MAX_CORRECTIONS},
+    services::translate::translate_word,
 };
 use axum::{
     extract::{Path, Query, State},
-    Json,
+    Extension, Json,
 };
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use validator::Validate;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
@@ -22,6 +36,50 @@ pub struct SearchQuery {
     limit: usize,
     pos: Option<String>,
     exact_match: Option<bool>,
+    lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    q: String,
+    #[serde(default = "default_suggest_limit")]
+    limit: usize,
+    lang: Option<String>,
+}
+
+fn default_suggest_limit() -> usize {
+    10
+}
+
+const MAX_SUGGEST_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageQuery {
+    lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelationsQuery {
+    #[serde(rename = "type")]
+    rel_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    relation: Option<String>,
+    depth: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimilarityQuery {
+    a: String,
+    b: String,
+    lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranslateQuery {
+    to: String,
 }
 
 fn default_offset() -> usize {
@@ -34,9 +92,17 @@ fn default_limit() -> usize {
 
 pub async fn search(
     State(state): State<AppState>,
+    user: Option<Extension<User>>,
     Query(query): Query<SearchQuery>,
 ) -> Result<Json<serde_json::Value>, AppError> {
+    if let Some(Extension(user)) = &user {
+        record_lookup(&state.db, user, &query.q);
+    }
+
     let filters = SearchFilters {
+        lang: query
+            .lang
+            .unwrap_or_else(|| state.config.default_language.clone()),
         pos: query.pos,
         exact_match: query.exact_match,
     };
@@ -58,97 +124,393 @@ pub async fn search(
 
 pub async fn get_word(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let word_result = get_word_by_exact_match(&state.meili, &word).await?;
+    if let Some(Extension(user)) = &user {
+        // History keeps what was typed, not the lemma it resolved to.
+        let typed = lemma
+            .as_ref()
+            .map_or(&word, |resolution| &resolution.inflected);
+        record_lookup(&state.db, user, typed);
+    }
+
+    let word_result = get_word_by_exact_match(&state.meili, &word, &lang).await?;
 
     match word_result {
         Some(word_obj) => Ok(Json(serde_json::json!({
             "status": "success",
+            "lemma": lemma,
             "word": word_obj,
         }))),
-        None => Err(AppError::NotFound(format!("Word '{}' not found", word))),
+        None => Err(word_not_found(&word, &lang)),
     }
 }
 
-pub async fn get_synonyms(
+/// Lemmas starting with `q`, for the search bar. Empty until autocomplete
+/// has been built after startup.
+pub async fn suggest(
+    State(state): State<AppState>,
+    Query(query): Query<SuggestQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if query.limit > MAX_SUGGEST_LIMIT {
+        return Err(AppError::BadRequest(format!(
+            "limit must be at most {}",
+            MAX_SUGGEST_LIMIT
+        )));
+    }
+
+    let lang = query
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    let suggestions = autocomplete::current()
+        .map(|autocomplete| autocomplete.complete(&lang, &query.q, query.limit))
+        .unwrap_or_default();
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "query": query.q,
+        "suggestions": suggestions,
+    })))
+}
+
+/// Spelling corrections for `word`. `known` says whether it is already a
+/// lemma.
+pub async fn spellcheck(
     State(state): State<AppState>,
     Path(word): Path<String>,
+    Query(query): Query<LanguageQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let lang = query
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    let known =
+        autocomplete::current().is_some_and(|autocomplete| autocomplete.contains(&lang, &word));
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "word": word,
+        "known": known,
+        "suggestions": corrections(&lang, &word, MAX_CORRECTIONS),
+    })))
+}
+
+/// Looks up many words in one request. Words that are not in the index map
+/// to `null`; inflected words are looked up by lemma and listed in `lemmas`.
+pub async fn batch_words(
+    State(state): State<AppState>,
+    Json(payload): Json<BatchWordsSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let keep = payload
+        .relations
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| {
+                    RelationType::from_name(name).ok_or_else(|| {
+                        AppError::ValidationError(format!("Unknown relation '{}'", name))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let words: Vec<String> = payload
+        .words
+        .iter()
+        .map(|word| word.trim().to_string())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let lang = payload
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    let lemmas: BTreeMap<String, Resolution> = words
+        .iter()
+        .filter_map(|word| resolve(&lang, word).map(|resolution| (word.clone(), resolution)))
+        .collect();
+
+    let lookups: Vec<String> = words
+        .iter()
+        .map(|word| lemmas.get(word).map_or(word, |r| &r.lemma).clone())
+        .collect();
+
+    let mut found = get_words_by_exact_match(&state.meili, &lookups, &lang).await?;
+
+    if let Some(keep) = &keep {
+        for word in found.values_mut().flatten() {
+            retain_relations(word, keep);
+        }
+    }
+
+    // Keyed by the words as sent, even when an inflection was looked up
+    // under its lemma.
+    let results: BTreeMap<&String, Option<&Word>> = words
+        .iter()
+        .zip(&lookups)
+        .map(|(word, lookup)| (word, found.get(lookup).and_then(Option::as_ref)))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "language": lang,
+        "words": results,
+        "lemmas": lemmas,
+    })))
+}
+
+pub async fn get_senses(
+    State(state): State<AppState>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let synonyms = get_relations(&state.meili, &word, RelationType::Synonym).await?;
+    let groups = get_word_senses(&state.meili, &word, &lang).await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
+        "word": word,
+        "senses": groups,
+    })))
+}
+
+pub async fn translate(
+    State(state): State<AppState>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
+    Query(query): Query<TranslateQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let translations = translate_word(&state.meili, &word, &lang, &query.to).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "lemma": lemma,
+        "word": word,
+        "from": lang,
+        "to": query.to,
+        "translations": translations,
+    })))
+}
+
+pub async fn get_graph(
+    State(state): State<AppState>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
+    Query(query): Query<GraphQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let relation = query.relation.as_deref();
+    let depth = query.depth.unwrap_or_else(|| default_depth(relation));
+
+    if depth > MAX_GRAPH_DEPTH {
+        return Err(AppError::BadRequest(format!(
+            "depth must be at most {}",
+            MAX_GRAPH_DEPTH
+        )));
+    }
+
+    let graph = build_relation_graph(&state.meili, &word, &lang, relation, depth).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "lemma": lemma,
+        "graph": graph,
+    })))
+}
+
+pub async fn similarity(
+    State(state): State<AppState>,
+    Query(query): Query<SimilarityQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let lang = query
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    // Inflections are compared by their lemmas, as on the other word routes.
+    let lemma_a = resolve(&lang, &query.a);
+    let lemma_b = resolve(&lang, &query.b);
+    let a = lemma_a.as_ref().map_or(&query.a, |r| &r.lemma);
+    let b = lemma_b.as_ref().map_or(&query.b, |r| &r.lemma);
+
+    let similarity = compare_words(&state.meili, a, b, &lang).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "similarity": similarity,
+        "lemmas": { "a": lemma_a, "b": lemma_b },
+    })))
+}
+
+pub async fn get_synonyms(
+    State(state): State<AppState>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let (synonyms, suggestions) = get_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+        RelationType::Synonym,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "lemma": lemma,
         "word": word,
         "relation": RelationType::Synonym.display_name(),
         "related_words": synonyms,
+        "suggestions": suggestions,
     })))
 }
 
 pub async fn get_antonyms(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let antonyms = get_relations(&state.meili, &word, RelationType::Antonym).await?;
+    let (antonyms, suggestions) = get_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+        RelationType::Antonym,
+    )
+    .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "relation": RelationType::Antonym.display_name(),
         "related_words": antonyms,
+        "suggestions": suggestions,
     })))
 }
 
 pub async fn get_broader_terms(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let hypernyms = get_relations(&state.meili, &word, RelationType::BroaderTerm).await?;
+    let (hypernyms, suggestions) = get_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+        RelationType::BroaderTerm,
+    )
+    .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "relation": RelationType::BroaderTerm.display_name(),
         "related_words": hypernyms,
+        "suggestions": suggestions,
     })))
 }
 
 pub async fn get_narrower_terms(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let hyponyms = get_relations(&state.meili, &word, RelationType::NarrowerTerm).await?;
+    let (hyponyms, suggestions) = get_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+        RelationType::NarrowerTerm,
+    )
+    .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "relation": RelationType::NarrowerTerm.display_name(),
         "related_words": hyponyms,
+        "suggestions": suggestions,
     })))
 }
 
 pub async fn get_related_terms(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let related = get_relations(&state.meili, &word, RelationType::RelatedTerm).await?;
+    let (related, suggestions) = get_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+        RelationType::RelatedTerm,
+    )
+    .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "relation": RelationType::RelatedTerm.display_name(),
         "related_words": related,
+        "suggestions": suggestions,
+    })))
+}
+
+pub async fn get_relations_by_type(
+    State(state): State<AppState>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
+    Query(query): Query<RelationsQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let relations =
+        get_typed_relations(&state.meili, &word, &lang, query.rel_type.as_deref()).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "lemma": lemma,
+        "word": word,
+        "type": query.rel_type,
+        "relations": relations,
+    })))
+}
+
+pub async fn list_relation_types(
+    State(state): State<AppState>,
+    Query(query): Query<LanguageQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let lang = query
+        .lang
+        .unwrap_or_else(|| state.config.default_language.clone());
+
+    let relation_types = get_relation_types(&state.meili, &lang).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "relation_types": relation_types,
     })))
 }
 
 pub async fn get_definition(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let definitions = get_word_definition(&state.meili, &word).await?;
+    let definitions = get_word_definition(&state.meili, &word, &lang).await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "definitions": definitions,
     })))
@@ -156,12 +518,13 @@ pub async fn get_definition(
 
 pub async fn get_examples(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let examples = get_word_examples(&state.meili, &word).await?;
+    let examples = get_word_examples(&state.meili, &word, &lang).await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word,
         "examples": examples,
     })))
@@ -169,12 +532,21 @@ pub async fn get_examples(
 
 pub async fn get_all_relations(
     State(state): State<AppState>,
-    Path(word): Path<String>,
+    user: Option<Extension<User>>,
+    LemmaPath { word, lang, lemma }: LemmaPath,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let word_data = get_all_word_relations(&state.meili, &word).await?;
+    let word_data = get_all_word_relations(
+        &state.meili,
+        &state.db,
+        user.as_ref().map(|Extension(user)| user),
+        &word,
+        &lang,
+    )
+    .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
+        "lemma": lemma,
         "word": word_data,
     })))
 }
@@ -0,0 +1,298 @@
+use crate::{
+    db::AppState,
+    error::AppError,
+    models::{
+        user::User,
+        word_list::{
+            AddEntrySchema, CreateWordListSchema, ExportQuery, ReorderEntriesSchema,
+            UpdateEntrySchema, UpdateWordListSchema, WordListWithEntries,
+        },
+    },
+    services::word_lists::{
+        add_entry, create_word_list, delete_entry, delete_word_list, export_csv,
+        get_shared_word_list, get_word_list, list_word_lists, reorder_entries, share_word_list,
+        unshare_word_list, update_entry, update_word_list,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Shared lists can be unshared at any time, so caches must revalidate
+/// before every reuse instead of serving them for `HTTP_CACHE_MAX_AGE`.
+const SHARED_CACHE_CONTROL: &str = "no-cache";
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let lists = list_word_lists(&state.db, &user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "lists": lists,
+    })))
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(payload): Json<CreateWordListSchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let list = create_word_list(
+        &state.db,
+        &user.id,
+        payload.name.trim(),
+        payload.description.as_deref(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "list": list,
+        })),
+    ))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let list = get_word_list(&state.db, &user.id, &list_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "list": list,
+    })))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+    Json(payload): Json<UpdateWordListSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let list = update_word_list(
+        &state.db,
+        &user.id,
+        &list_id,
+        payload.name.as_deref().map(str::trim),
+        payload.description.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "list": list,
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    delete_word_list(&state.db, &user.id, &list_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+    })))
+}
+
+pub async fn add_list_entry(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+    Json(payload): Json<AddEntrySchema>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let entry = add_entry(
+        &state.db,
+        &user.id,
+        &list_id,
+        payload.word.trim(),
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "status": "success",
+            "entry": entry,
+        })),
+    ))
+}
+
+pub async fn update_list_entry(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path((list_id, entry_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateEntrySchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let entry = update_entry(
+        &state.db,
+        &user.id,
+        &list_id,
+        &entry_id,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "entry": entry,
+    })))
+}
+
+pub async fn delete_list_entry(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path((list_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    delete_entry(&state.db, &user.id, &list_id, &entry_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+    })))
+}
+
+pub async fn reorder(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+    Json(payload): Json<ReorderEntriesSchema>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let entries = reorder_entries(&state.db, &user.id, &list_id, &payload.entry_ids).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "entries": entries,
+    })))
+}
+
+pub async fn share(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let list = share_word_list(&state.db, &user.id, &list_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "share_token": list.share_token,
+        "list": list,
+    })))
+}
+
+pub async fn unshare(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let list = unshare_word_list(&state.db, &user.id, &list_id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "list": list,
+    })))
+}
+
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(list_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let list = get_word_list(&state.db, &user.id, &list_id).await?;
+
+    export_response(list, query.format.as_deref())
+}
+
+pub async fn get_shared(
+    State(state): State<AppState>,
+    Path(share_token): Path<String>,
+) -> Result<Response, AppError> {
+    let mut list = get_shared_word_list(&state.db, &share_token).await?;
+    list.list.share_token = None;
+
+    let body = Json(json!({
+        "status": "success",
+        "list": list,
+    }));
+
+    Ok(([(header::CACHE_CONTROL, SHARED_CACHE_CONTROL)], body).into_response())
+}
+
+pub async fn export_shared(
+    State(state): State<AppState>,
+    Path(share_token): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let mut list = get_shared_word_list(&state.db, &share_token).await?;
+    list.list.share_token = None;
+
+    let mut response = export_response(list, query.format.as_deref())?;
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(SHARED_CACHE_CONTROL),
+    );
+
+    Ok(response)
+}
+
+fn export_response(list: WordListWithEntries, format: Option<&str>) -> Result<Response, AppError> {
+    let filename: String = list
+        .list
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    let (content_type, extension, body) = match format.unwrap_or("json") {
+        "csv" => ("text/csv; charset=utf-8", "csv", export_csv(&list)),
+        "json" => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&list)
+                .map_err(|e| AppError::InternalServerError(e.to_string()))?,
+        ),
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unsupported export format '{}', expected 'csv' or 'json'",
+                other
+            )))
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}.{}\"", filename, extension)
+            .parse()
+            .unwrap(),
+    );
+
+    Ok((headers, body).into_response())
+}
@@ -0,0 +1,307 @@
+use crate::{
+    config::Config,
+    error::AppError,
+    models::user::User,
+    services::{
+        mailer::{Email, Mailer},
+        sessions::revoke_all_sessions,
+        tokens::{generate_opaque_token, hash_token},
+    },
+};
+use bcrypt::{hash, DEFAULT_COST};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const PASSWORD_RESET: &str = "password_reset";
+const EMAIL_VERIFICATION: &str = "email_verification";
+
+const PASSWORD_RESET_TTL_MINUTES: i32 = 60;
+const EMAIL_VERIFICATION_TTL_MINUTES: i32 = 48 * 60;
+
+/// Issues a single-use token for `purpose`, invalidating any unused ones the
+/// user still had for it.
+async fn create_auth_token(
+    pool: &PgPool,
+    user_id: &Uuid,
+    purpose: &str,
+    ttl_minutes: i32,
+) -> Result<String, AppError> {
+    let token = generate_opaque_token();
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM auth_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL")
+        .bind(user_id)
+        .bind(purpose)
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO auth_tokens (id, user_id, purpose, token_hash, expires_at) \
+         VALUES ($1, $2, $3, $4, NOW() + make_interval(mins => $5))",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(purpose)
+    .bind(hash_token(&token))
+    .bind(ttl_minutes)
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(token)
+}
+
+/// Marks a token as used and returns its user, unless it is unknown,
+/// expired or already used.
+async fn consume_auth_token<'e, E>(
+    executor: E,
+    token: &str,
+    purpose: &str,
+) -> Result<Uuid, AppError>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    sqlx::query_scalar(
+        "UPDATE auth_tokens SET used_at = NOW() \
+         WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW() \
+         RETURNING user_id",
+    )
+    .bind(hash_token(token))
+    .bind(purpose)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Invalid or expired token".to_string()))
+}
+
+/// Emails a password reset link if an account exists for `email`. Says
+/// nothing either way so the endpoint cannot be used to probe for accounts.
+pub async fn request_password_reset(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    config: &Config,
+    email: &str,
+) -> Result<(), AppError> {
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(pool)
+        .await?;
+
+    let Some(user) = user else {
+        return Ok(());
+    };
+
+    let token =
+        create_auth_token(pool, &user.id, PASSWORD_RESET, PASSWORD_RESET_TTL_MINUTES).await?;
+
+    mailer
+        .send(&Email {
+            to: user.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {},\n\nUse the link below to choose a new password. It expires in {} minutes.\n\n{}/reset-password?token={}\n\nIf you did not ask for this, you can ignore this email.",
+                user.name, PASSWORD_RESET_TTL_MINUTES, config.app_url, token
+            ),
+        })
+        .await
+}
+
+/// Sets a new password and signs the user out everywhere. Resetting through
+/// an emailed link also proves the address, so it counts as verified.
+pub async fn reset_password(pool: &PgPool, token: &str, password: &str) -> Result<(), AppError> {
+    let hashed_password = hash(password, DEFAULT_COST)?;
+
+    let mut tx = pool.begin().await?;
+
+    let user_id = consume_auth_token(&mut tx, token, PASSWORD_RESET).await?;
+
+    sqlx::query(
+        "UPDATE users SET password = $1, \
+             email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() \
+         WHERE id = $2",
+    )
+    .bind(hashed_password)
+    .bind(user_id)
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    revoke_all_sessions(pool, &user_id).await?;
+
+    Ok(())
+}
+
+pub async fn send_verification_email(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    config: &Config,
+    user: &User,
+) -> Result<(), AppError> {
+    if user.email_verified_at.is_some() {
+        return Err(AppError::BadRequest(
+            "Email is already verified".to_string(),
+        ));
+    }
+
+    let token = create_auth_token(
+        pool,
+        &user.id,
+        EMAIL_VERIFICATION,
+        EMAIL_VERIFICATION_TTL_MINUTES,
+    )
+    .await?;
+
+    mailer
+        .send(&Email {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address by opening the link below. It expires in {} hours.\n\n{}/verify-email?token={}",
+                user.name,
+                EMAIL_VERIFICATION_TTL_MINUTES / 60,
+                config.app_url,
+                token
+            ),
+        })
+        .await
+}
+
+pub async fn verify_email(pool: &PgPool, token: &str) -> Result<User, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user_id = consume_auth_token(&mut tx, token, EMAIL_VERIFICATION).await?;
+
+    let user = sqlx::query_as::<_, User>(
+        "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() \
+         WHERE id = $1 RETURNING *",
+    )
+    .bind(user_id)
+    .fetch_one(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        services::mailer::RecordingMailer,
+        testing::{create_user, test_config, test_pool},
+    };
+
+    /// The token in the link of the last email sent.
+    fn sent_token(mailer: &RecordingMailer) -> String {
+        let sent = mailer.sent.lock().unwrap();
+        let body = &sent.last().unwrap().body;
+        let start = body.find("token=").unwrap() + "token=".len();
+        body[start..].split_whitespace().next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn verification_token_is_single_use() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user = create_user(&pool).await;
+        let mailer = RecordingMailer::default();
+
+        send_verification_email(&pool, &mailer, &test_config(), &user)
+            .await
+            .unwrap();
+        let token = sent_token(&mailer);
+        assert!(mailer.sent.lock().unwrap()[0]
+            .body
+            .contains("http://localhost:3000/verify-email?token="));
+
+        let verified = verify_email(&pool, &token).await.unwrap();
+        assert_eq!(verified.id, user.id);
+        assert!(verified.email_verified_at.is_some());
+
+        assert!(matches!(
+            verify_email(&pool, &token).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reset_token_is_single_use() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user = create_user(&pool).await;
+        let mailer = RecordingMailer::default();
+
+        request_password_reset(&pool, &mailer, &test_config(), &user.email)
+            .await
+            .unwrap();
+        let token = sent_token(&mailer);
+
+        reset_password(&pool, &token, "new password").await.unwrap();
+        assert!(matches!(
+            reset_password(&pool, &token, "another password").await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let password: Option<String> =
+            sqlx::query_scalar("SELECT password FROM users WHERE id = $1")
+                .bind(user.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(bcrypt::verify("new password", &password.unwrap()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_rejected() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user = create_user(&pool).await;
+
+        let token = create_auth_token(&pool, &user.id, PASSWORD_RESET, -1)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            reset_password(&pool, &token, "new password").await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn token_is_only_valid_for_its_purpose() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user = create_user(&pool).await;
+
+        let token = create_auth_token(&pool, &user.id, PASSWORD_RESET, 60)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            verify_email(&pool, &token).await,
+            Err(AppError::BadRequest(_))
+        ));
+        reset_password(&pool, &token, "new password").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reset_email_is_not_sent_for_unknown_addresses() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let mailer = RecordingMailer::default();
+
+        request_password_reset(&pool, &mailer, &test_config(), "nobody@example.invalid")
+            .await
+            .unwrap();
+
+        assert!(mailer.sent.lock().unwrap().is_empty());
+    }
+}
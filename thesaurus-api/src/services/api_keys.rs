@@ -0,0 +1,149 @@
+use crate::{
+    error::AppError,
+    models::api_key::ApiKey,
+    services::tokens::{generate_opaque_token, hash_token},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const KEY_PREFIX: &str = "fsk_";
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+pub async fn list_api_keys(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ApiKey>, AppError> {
+    let keys = sqlx::query_as::<_, ApiKey>(
+        "SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(keys)
+}
+
+/// Creates a key and returns it with the plain key, which is not stored.
+pub async fn create_api_key(
+    pool: &PgPool,
+    user_id: &Uuid,
+    name: &str,
+    scopes: &[String],
+) -> Result<(ApiKey, String), AppError> {
+    let key = format!("{}{}", KEY_PREFIX, generate_opaque_token());
+
+    let api_key = sqlx::query_as::<_, ApiKey>(
+        "INSERT INTO api_keys (id, user_id, name, prefix, key_hash, scopes) \
+         VALUES ($1, $2, $3, $4, $5, $6) \
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(name)
+    .bind(&key[..DISPLAY_PREFIX_LENGTH])
+    .bind(hash_token(&key))
+    .bind(scopes)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((api_key, key))
+}
+
+pub async fn revoke_api_key(
+    pool: &PgPool,
+    user_id: &Uuid,
+    key_id: &Uuid,
+) -> Result<ApiKey, AppError> {
+    sqlx::query_as::<_, ApiKey>(
+        "UPDATE api_keys SET revoked_at = NOW() \
+         WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL \
+         RETURNING *",
+    )
+    .bind(key_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("API key '{}' not found", key_id)))
+}
+
+/// The active key matching `key`, if any.
+pub async fn find_api_key(pool: &PgPool, key: &str) -> Result<Option<ApiKey>, AppError> {
+    if !key.starts_with(KEY_PREFIX) {
+        return Ok(None);
+    }
+
+    let api_key = sqlx::query_as::<_, ApiKey>(
+        "SELECT * FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+    )
+    .bind(hash_token(key))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(api_key)
+}
+
+/// Records that a key was used without holding up the request. Writes are
+/// skipped if the key was already marked as used within the last minute.
+pub fn touch_api_key(pool: &PgPool, key_id: &Uuid) {
+    let pool = pool.clone();
+    let key_id = *key_id;
+
+    tokio::spawn(async move {
+        let result = sqlx::query(
+            "UPDATE api_keys SET last_used_at = NOW() \
+             WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')",
+        )
+        .bind(key_id)
+        .execute(&pool)
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to record API key use: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_user, test_pool};
+
+    #[tokio::test]
+    async fn keys_without_the_prefix_are_not_looked_up() {
+        // Never connects: the prefix check comes first.
+        let pool = PgPool::connect_lazy("postgres://nobody@127.0.0.1:1/none").unwrap();
+
+        let key = generate_opaque_token();
+        assert!(find_api_key(&pool, &key).await.unwrap().is_none());
+        assert!(find_api_key(&pool, "").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn keys_are_stored_hashed_and_found_by_hash() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user = create_user(&pool).await;
+
+        let (api_key, key) = create_api_key(&pool, &user.id, "test", &["lists".to_string()])
+            .await
+            .unwrap();
+
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(api_key.prefix, key[..DISPLAY_PREFIX_LENGTH]);
+
+        let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = $1")
+            .bind(api_key.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, hash_token(&key));
+
+        let found = find_api_key(&pool, &key).await.unwrap().unwrap();
+        assert_eq!(found.id, api_key.id);
+        assert!(find_api_key(&pool, &format!("{}x", key))
+            .await
+            .unwrap()
+            .is_none());
+
+        revoke_api_key(&pool, &user.id, &api_key.id).await.unwrap();
+        assert!(find_api_key(&pool, &key).await.unwrap().is_none());
+    }
+}
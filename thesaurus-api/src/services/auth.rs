@@ -46,7 +46,12 @@ pub async fn login_user(pool: &PgPool, email: &str, password: &str) -> Result<Us
 
     let user = user.ok_or(AppError::InvalidCredentials)?;
 
-    let is_valid = verify(password, &user.password)?;
+    let hashed_password = user
+        .password
+        .as_deref()
+        .ok_or(AppError::InvalidCredentials)?;
+
+    let is_valid = verify(password, hashed_password)?;
     if !is_valid {
         return Err(AppError::InvalidCredentials);
     }
@@ -54,12 +59,18 @@ pub async fn login_user(pool: &PgPool, email: &str, password: &str) -> Result<Us
     Ok(user)
 }
 
-pub fn generate_token(config: &Config, user_id: &Uuid) -> Result<String, AppError> {
+/// Issues a short-lived access token for `user_id` within session `session_id`.
+pub fn generate_token(
+    config: &Config,
+    user_id: &Uuid,
+    session_id: &Uuid,
+) -> Result<String, AppError> {
     let now = chrono::Utc::now();
     let iat = now.timestamp() as usize;
-    let exp = (now + chrono::Duration::minutes(config.jwt_maxage)).timestamp() as usize;
+    let exp = (now + chrono::Duration::minutes(config.jwt_expires_in)).timestamp() as usize;
     let claims = TokenClaims {
         sub: user_id.to_string(),
+        sid: session_id.to_string(),
         iat,
         exp,
     };
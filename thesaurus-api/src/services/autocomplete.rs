@@ -0,0 +1,345 @@
+use crate::error::AppError;
+use fst::{
+    automaton::{Levenshtein, Str},
+    Automaton, IntoStreamer, Map, MapBuilder, Streamer,
+};
+use meilisearch_sdk::{client::Client as MeiliClient, documents::DocumentsQuery};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+const PAGE_SIZE: usize = 10_000;
+
+static AUTOCOMPLETE: Lazy<RwLock<Option<Arc<Autocomplete>>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Deserialize)]
+struct LemmaDocument {
+    word: String,
+    language: String,
+    #[serde(default)]
+    definitions: Vec<String>,
+    #[serde(default)]
+    pos: Vec<String>,
+    #[serde(default)]
+    forms: Vec<String>,
+}
+
+/// A lemma offered for a prefix. `senses` is the number of WordNet senses,
+/// which stands in for how common the word is.
+#[derive(Debug, Clone, Serialize)]
+pub struct Completion {
+    pub word: String,
+    pub senses: u32,
+    pub pos: Vec<String>,
+}
+
+struct LanguageIndex {
+    /// Lowercased lemma to its position in `completions`.
+    keys: Map<Vec<u8>>,
+    completions: Vec<Vec<Completion>>,
+    /// Lowercased inflected form to the lowercased lemmas it belongs to.
+    forms: HashMap<String, Vec<String>>,
+}
+
+/// Per-language FSTs over every lemma in the `words` index, so prefix
+/// lookups, spelling corrections and lemmatization never touch Meilisearch.
+pub struct Autocomplete {
+    languages: HashMap<String, LanguageIndex>,
+}
+
+impl Autocomplete {
+    fn build(lemmas: Vec<LemmaDocument>) -> Result<Self, AppError> {
+        let mut by_language: BTreeMap<String, BTreeMap<String, Vec<Completion>>> = BTreeMap::new();
+        let mut forms_by_language: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+
+        for lemma in lemmas {
+            let senses = lemma.definitions.len() as u32;
+            let key = lemma.word.to_lowercase();
+
+            let forms = forms_by_language.entry(lemma.language.clone()).or_default();
+            for form in &lemma.forms {
+                let lemmas = forms.entry(form.to_lowercase()).or_default();
+                if !lemmas.contains(&key) {
+                    lemmas.push(key.clone());
+                }
+            }
+
+            let completions = by_language
+                .entry(lemma.language)
+                .or_default()
+                .entry(key)
+                .or_default();
+
+            // The same lemma can come from several lexicons.
+            match completions.iter_mut().find(|c| c.word == lemma.word) {
+                Some(existing) => {
+                    existing.senses = existing.senses.max(senses);
+                    for pos in lemma.pos {
+                        if !existing.pos.contains(&pos) {
+                            existing.pos.push(pos);
+                        }
+                    }
+                }
+                None => completions.push(Completion {
+                    word: lemma.word,
+                    senses,
+                    pos: lemma.pos,
+                }),
+            }
+        }
+
+        let mut languages = HashMap::new();
+
+        for (language, lemmas) in by_language {
+            let mut builder = MapBuilder::memory();
+            let mut completions = Vec::with_capacity(lemmas.len());
+
+            for (key, entries) in lemmas {
+                builder
+                    .insert(&key, completions.len() as u64)
+                    .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+                completions.push(entries);
+            }
+
+            let keys = builder.into_map();
+
+            let forms = forms_by_language.remove(&language).unwrap_or_default();
+
+            languages.insert(
+                language,
+                LanguageIndex {
+                    keys,
+                    completions,
+                    forms,
+                },
+            );
+        }
+
+        Ok(Self { languages })
+    }
+
+    /// Lemmas starting with `prefix`, ignoring case, most senses first.
+    pub fn complete(&self, lang: &str, prefix: &str, limit: usize) -> Vec<Completion> {
+        let prefix = prefix.trim().to_lowercase();
+
+        let Some(index) = self.languages.get(lang) else {
+            return Vec::new();
+        };
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        let mut stream = index
+            .keys
+            .search(Str::new(&prefix).starts_with())
+            .into_stream();
+
+        while let Some((_, position)) = stream.next() {
+            matches.extend(&index.completions[position as usize]);
+        }
+
+        matches.sort_by(|a, b| {
+            b.senses
+                .cmp(&a.senses)
+                .then_with(|| a.word.len().cmp(&b.word.len()))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+
+        matches.into_iter().take(limit).cloned().collect()
+    }
+
+    /// Whether `word` is a lemma, ignoring case.
+    pub fn contains(&self, lang: &str, word: &str) -> bool {
+        self.lemma(lang, word).is_some()
+    }
+
+    /// The lemmas spelt `word`, ignoring case.
+    pub fn lemma(&self, lang: &str, word: &str) -> Option<&[Completion]> {
+        let index = self.languages.get(lang)?;
+        let position = index.keys.get(word.to_lowercase())?;
+
+        Some(&index.completions[position as usize])
+    }
+
+    /// The lemmas that list `form` as one of their inflected forms.
+    pub fn lemmas_with_form(&self, lang: &str, form: &str) -> Vec<&Completion> {
+        let Some(index) = self.languages.get(lang) else {
+            return Vec::new();
+        };
+
+        index
+            .forms
+            .get(&form.to_lowercase())
+            .into_iter()
+            .flatten()
+            .filter_map(|key| index.keys.get(key))
+            .flat_map(|position| &index.completions[position as usize])
+            .collect()
+    }
+
+    /// Lemmas within `distance` Levenshtein edits of `word`, ignoring case.
+    pub fn fuzzy(&self, lang: &str, word: &str, distance: u32) -> Vec<Completion> {
+        let Some(index) = self.languages.get(lang) else {
+            return Vec::new();
+        };
+        // Very long inputs can blow up the automaton; they get no matches.
+        let Ok(automaton) = Levenshtein::new(&word.to_lowercase(), distance) else {
+            return Vec::new();
+        };
+
+        let mut matches = Vec::new();
+        let mut stream = index.keys.search(automaton).into_stream();
+
+        while let Some((_, position)) = stream.next() {
+            matches.extend(index.completions[position as usize].iter().cloned());
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+impl Autocomplete {
+    /// An index over `lemmas`, given as `words` documents.
+    pub fn from_documents(lemmas: serde_json::Value) -> Self {
+        Self::build(serde_json::from_value(lemmas).unwrap()).unwrap()
+    }
+}
+
+/// The autocomplete index, once it has been built.
+pub fn current() -> Option<Arc<Autocomplete>> {
+    AUTOCOMPLETE.read().unwrap().clone()
+}
+
+/// Rebuilds the autocomplete index from the `words` index and swaps it in.
+/// Returns the number of lemmas read.
+pub async fn rebuild(client: &MeiliClient) -> Result<usize, AppError> {
+    let index = client.index("words");
+    let mut lemmas: Vec<LemmaDocument> = Vec::new();
+
+    loop {
+        let page = DocumentsQuery::new(&index)
+            .with_fields(["word", "language", "definitions", "pos", "forms"])
+            .with_offset(lemmas.len())
+            .with_limit(PAGE_SIZE)
+            .execute::<LemmaDocument>()
+            .await?;
+
+        let fetched = page.results.len();
+        lemmas.extend(page.results);
+
+        if fetched < PAGE_SIZE {
+            break;
+        }
+    }
+
+    let count = lemmas.len();
+    let autocomplete = Autocomplete::build(lemmas)?;
+    *AUTOCOMPLETE.write().unwrap() = Some(Arc::new(autocomplete));
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn words(completions: Vec<Completion>) -> Vec<String> {
+        completions.into_iter().map(|c| c.word).collect()
+    }
+
+    #[test]
+    fn complete_matches_prefixes_ignoring_case() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "Bank", "language": "en" },
+            { "word": "banner", "language": "en" },
+            { "word": "bond", "language": "en" },
+        ]));
+
+        assert_eq!(
+            words(autocomplete.complete("en", "BAN", 10)),
+            vec!["Bank", "banner"]
+        );
+        assert_eq!(
+            words(autocomplete.complete("en", " bank ", 10)),
+            vec!["Bank"]
+        );
+    }
+
+    #[test]
+    fn complete_orders_by_senses_then_length_then_word() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "cart", "language": "en", "definitions": ["a"] },
+            { "word": "car", "language": "en", "definitions": ["a"] },
+            { "word": "cat", "language": "en", "definitions": ["a"] },
+            { "word": "cargo", "language": "en", "definitions": ["a", "b"] },
+        ]));
+
+        assert_eq!(
+            words(autocomplete.complete("en", "ca", 10)),
+            vec!["cargo", "car", "cat", "cart"]
+        );
+    }
+
+    #[test]
+    fn complete_respects_the_limit() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "dog", "language": "en", "definitions": ["a", "b"] },
+            { "word": "dot", "language": "en", "definitions": ["a"] },
+            { "word": "dove", "language": "en" },
+        ]));
+
+        assert_eq!(
+            words(autocomplete.complete("en", "do", 2)),
+            vec!["dog", "dot"]
+        );
+        assert!(autocomplete.complete("en", "do", 0).is_empty());
+    }
+
+    #[test]
+    fn complete_returns_nothing_for_an_empty_prefix_or_unknown_language() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "egg", "language": "en" },
+        ]));
+
+        assert!(autocomplete.complete("en", "", 10).is_empty());
+        assert!(autocomplete.complete("en", "   ", 10).is_empty());
+        assert!(autocomplete.complete("fr", "e", 10).is_empty());
+    }
+
+    #[test]
+    fn complete_merges_a_lemma_from_several_lexicons() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "run", "language": "en", "definitions": ["a"], "pos": ["v"] },
+            { "word": "run", "language": "en", "definitions": ["a", "b", "c"], "pos": ["n", "v"] },
+        ]));
+
+        let completions = autocomplete.complete("en", "ru", 10);
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].senses, 3);
+        assert_eq!(completions[0].pos, vec!["v", "n"]);
+    }
+
+    #[test]
+    fn lemmas_with_form_finds_lemmas_by_inflection() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "Mouse", "language": "en", "forms": ["mice"] },
+            { "word": "louse", "language": "en", "forms": ["lice"] },
+            { "word": "souris", "language": "fr", "forms": ["mice"] },
+        ]));
+
+        let lemmas: Vec<&str> = autocomplete
+            .lemmas_with_form("en", "MICE")
+            .into_iter()
+            .map(|c| c.word.as_str())
+            .collect();
+        assert_eq!(lemmas, vec!["Mouse"]);
+        assert!(autocomplete.lemmas_with_form("en", "mouse").is_empty());
+        assert!(autocomplete.lemmas_with_form("de", "mice").is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{error::AppError, models::favorite::Favorite};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How far the sync cursor trails the database clock. A write that started
+/// before the listing but committed after it still falls inside the window;
+/// clients merge last-write-wins, so seeing a change twice is harmless.
+const SYNC_OVERLAP_SECS: f64 = 5.0;
+
+/// Active favorites, or with `since` every favorite changed after that time
+/// (tombstones included) so a client can merge them into its local copy.
+/// Also returns the `since` to send next time, taken from the database clock
+/// before the favorites are read.
+pub async fn list_favorites(
+    pool: &PgPool,
+    user_id: &Uuid,
+    since: Option<DateTime<Utc>>,
+) -> Result<(Vec<Favorite>, DateTime<Utc>), AppError> {
+    let cursor: DateTime<Utc> = sqlx::query_scalar("SELECT NOW() - make_interval(secs => $1)")
+        .bind(SYNC_OVERLAP_SECS)
+        .fetch_one(pool)
+        .await?;
+
+    let favorites = match since {
+        Some(since) => {
+            sqlx::query_as::<_, Favorite>(
+                "SELECT * FROM favorites WHERE user_id = $1 AND updated_at > $2 ORDER BY updated_at",
+            )
+            .bind(user_id)
+            .bind(since)
+            .fetch_all(pool)
+            .await?
+        }
+        None => {
+            sqlx::query_as::<_, Favorite>(
+                "SELECT * FROM favorites WHERE user_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC",
+            )
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?
+        }
+    };
+
+    Ok((favorites, cursor))
+}
+
+pub async fn get_favorite(pool: &PgPool, user_id: &Uuid, word: &str) -> Result<Favorite, AppError> {
+    sqlx::query_as::<_, Favorite>(
+        "SELECT * FROM favorites WHERE user_id = $1 AND word = $2 AND deleted_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(word)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Favorite '{}' not found", word)))
+}
+
+/// Adds a favorite, reviving it if it was previously removed. Offline
+/// changes are merged last-write-wins: a removal newer than `added_at`
+/// stands, and the tombstone is returned unchanged.
+pub async fn add_favorite(
+    pool: &PgPool,
+    user_id: &Uuid,
+    word: &str,
+    added_at: Option<DateTime<Utc>>,
+) -> Result<Favorite, AppError> {
+    let favorite = sqlx::query_as::<_, Favorite>(
+        "INSERT INTO favorites (id, user_id, word, created_at) \
+         VALUES ($1, $2, $3, COALESCE($4, NOW())) \
+         ON CONFLICT (user_id, word) DO UPDATE SET \
+             created_at = CASE WHEN favorites.deleted_at IS NULL \
+                 THEN favorites.created_at ELSE EXCLUDED.created_at END, \
+             updated_at = NOW(), \
+             deleted_at = NULL \
+         WHERE favorites.deleted_at IS NULL OR favorites.deleted_at <= EXCLUDED.created_at \
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(word)
+    .bind(added_at)
+    .fetch_optional(pool)
+    .await?;
+
+    match favorite {
+        Some(favorite) => Ok(favorite),
+        None => find_favorite(pool, user_id, word).await,
+    }
+}
+
+/// Marks a favorite as removed, leaving a tombstone for syncing clients.
+/// With `removed_at` (an offline removal) the removal only applies if it is
+/// newer than when the word was added; otherwise the favorite is returned
+/// unchanged. Removing an already removed favorite returns its tombstone.
+pub async fn remove_favorite(
+    pool: &PgPool,
+    user_id: &Uuid,
+    word: &str,
+    removed_at: Option<DateTime<Utc>>,
+) -> Result<Favorite, AppError> {
+    let favorite = sqlx::query_as::<_, Favorite>(
+        "UPDATE favorites SET deleted_at = COALESCE($3, NOW()), updated_at = NOW() \
+         WHERE user_id = $1 AND word = $2 AND deleted_at IS NULL \
+             AND ($3::TIMESTAMPTZ IS NULL OR created_at <= $3) \
+         RETURNING *",
+    )
+    .bind(user_id)
+    .bind(word)
+    .bind(removed_at)
+    .fetch_optional(pool)
+    .await?;
+
+    match favorite {
+        Some(favorite) => Ok(favorite),
+        None => find_favorite(pool, user_id, word).await,
+    }
+}
+
+/// A favorite whether or not it has been removed.
+async fn find_favorite(pool: &PgPool, user_id: &Uuid, word: &str) -> Result<Favorite, AppError> {
+    sqlx::query_as::<_, Favorite>("SELECT * FROM favorites WHERE user_id = $1 AND word = $2")
+        .bind(user_id)
+        .bind(word)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Favorite '{}' not found", word)))
+}
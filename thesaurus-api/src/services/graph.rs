@@ -0,0 +1,344 @@
+use crate::{
+    error::AppError,
+    models::word::{GraphEdge, GraphNode, RelationGraph, Synset},
+    services::search::{get_word_senses, quote_filter_value},
+};
+use meilisearch_sdk::{client::Client as MeiliClient, search::SearchResults};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+};
+
+/// Deep enough to reach the root from any synset in Open English WordNet.
+pub const MAX_GRAPH_DEPTH: usize = 25;
+
+const MAX_GRAPH_NODES: usize = 500;
+const SYNSET_FETCH_CHUNK: usize = 100;
+
+/// Fetches synsets by id from the `synsets` index. Unknown ids are skipped.
+pub async fn get_synsets(
+    client: &MeiliClient,
+    synset_ids: &[String],
+) -> Result<HashMap<String, Synset>, AppError> {
+    let index = client.index("synsets");
+    let mut synsets = HashMap::new();
+
+    for chunk in synset_ids.chunks(SYNSET_FETCH_CHUNK) {
+        let quoted: Vec<String> = chunk.iter().map(|id| quote_filter_value(id)).collect();
+        let filter_str = format!("synset_id IN [{}]", quoted.join(", "));
+
+        let mut search_query = index.search();
+        search_query
+            .with_filter(&filter_str)
+            .with_limit(chunk.len());
+
+        let search_results: SearchResults<Synset> = search_query.execute().await?;
+
+        for hit in search_results.hits {
+            synsets.insert(hit.result.synset_id.clone(), hit.result);
+        }
+    }
+
+    Ok(synsets)
+}
+
+/// Fetches `synset_ids` and every synset on their hypernym chains.
+pub async fn get_hypernym_closure(
+    client: &MeiliClient,
+    synset_ids: &[String],
+) -> Result<HashMap<String, Synset>, AppError> {
+    let mut synsets: HashMap<String, Synset> = HashMap::new();
+    let mut frontier: Vec<String> = synset_ids.to_vec();
+
+    for _ in 0..=MAX_GRAPH_DEPTH {
+        frontier.retain(|id| !synsets.contains_key(id));
+        frontier.sort();
+        frontier.dedup();
+
+        if frontier.is_empty() {
+            break;
+        }
+
+        let fetched = get_synsets(client, &frontier).await?;
+
+        frontier = fetched
+            .values()
+            .flat_map(|synset| synset.relations.iter())
+            .filter(|edge| matches_relation(Some("hypernym"), &edge.rel_type))
+            .map(|edge| edge.target.clone())
+            .collect();
+
+        synsets.extend(fetched);
+    }
+
+    Ok(synsets)
+}
+
+/// Whether an edge of type `rel_type` should be followed for `relation`.
+/// Hypernym and hyponym traversals also follow their instance variants, so
+/// named entities still reach the root of the "is-a" tree.
+pub fn matches_relation(relation: Option<&str>, rel_type: &str) -> bool {
+    match relation {
+        None => true,
+        Some("hypernym") => rel_type == "hypernym" || rel_type == "instance_hypernym",
+        Some("hyponym") => rel_type == "hyponym" || rel_type == "instance_hyponym",
+        Some(relation) => relation == rel_type,
+    }
+}
+
+/// Default traversal depth: the full chain to the root for hypernyms, one
+/// hop for everything else.
+pub fn default_depth(relation: Option<&str>) -> usize {
+    match relation {
+        Some("hypernym") => MAX_GRAPH_DEPTH,
+        _ => 1,
+    }
+}
+
+/// Walks `relation` edges (or all edges) breadth-first from every sense of
+/// `word`, up to `depth` hops.
+pub async fn build_relation_graph(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
+    relation: Option<&str>,
+    depth: usize,
+) -> Result<RelationGraph, AppError> {
+    let roots: Vec<String> = get_word_senses(client, word, lang)
+        .await?
+        .into_iter()
+        .flat_map(|group| group.senses)
+        .map(|sense| sense.synset_id)
+        .collect();
+
+    walk_relation_graph(word, roots, relation, depth, |ids| async move {
+        get_synsets(client, &ids).await
+    })
+    .await
+}
+
+/// The traversal behind [`build_relation_graph`], with synsets looked up
+/// through `fetch`. Each synset becomes one node at the depth it was first
+/// reached, and each edge is kept once, so cycles end the walk.
+async fn walk_relation_graph<F, Fut>(
+    word: &str,
+    mut roots: Vec<String>,
+    relation: Option<&str>,
+    depth: usize,
+    mut fetch: F,
+) -> Result<RelationGraph, AppError>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<HashMap<String, Synset>, AppError>>,
+{
+    let mut seen_roots = HashSet::new();
+    roots.retain(|id| seen_roots.insert(id.clone()));
+
+    let mut depths: HashMap<String, usize> = roots.iter().map(|id| (id.clone(), 0)).collect();
+    let mut order: Vec<String> = roots.clone();
+    let mut synsets: HashMap<String, Synset> = HashMap::new();
+    let mut edges: Vec<GraphEdge> = Vec::new();
+    let mut seen_edges = HashSet::new();
+    let mut truncated = false;
+
+    let mut frontier = roots.clone();
+
+    for level in 0..depth {
+        if frontier.is_empty() {
+            break;
+        }
+
+        synsets.extend(fetch(frontier.clone()).await?);
+
+        let mut next = Vec::new();
+
+        for source in &frontier {
+            let synset = match synsets.get(source) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            for relation_edge in &synset.relations {
+                if !matches_relation(relation, &relation_edge.rel_type) {
+                    continue;
+                }
+
+                if !depths.contains_key(&relation_edge.target) {
+                    if depths.len() >= MAX_GRAPH_NODES {
+                        truncated = true;
+                        continue;
+                    }
+
+                    depths.insert(relation_edge.target.clone(), level + 1);
+                    order.push(relation_edge.target.clone());
+                    next.push(relation_edge.target.clone());
+                }
+
+                let edge = GraphEdge {
+                    source: source.clone(),
+                    target: relation_edge.target.clone(),
+                    relation: relation_edge.rel_type.clone(),
+                };
+                if seen_edges.insert(edge.clone()) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        frontier = next;
+    }
+
+    // The last level was discovered but never expanded, so fetch it for its
+    // node details.
+    if !frontier.is_empty() {
+        synsets.extend(fetch(frontier).await?);
+    }
+
+    let nodes = order
+        .into_iter()
+        .filter_map(|id| {
+            let depth = depths[&id];
+            synsets.remove(&id).map(|synset| GraphNode {
+                synset_id: synset.synset_id,
+                pos: synset.pos,
+                definition: synset.definition,
+                lemmas: synset.lemmas,
+                depth,
+            })
+        })
+        .collect();
+
+    Ok(RelationGraph {
+        word: word.to_string(),
+        relation: relation.map(|r| r.to_string()),
+        depth,
+        roots,
+        nodes,
+        edges,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::word::SynsetRelation;
+
+    fn synset(id: &str, relations: &[(&str, &str)]) -> Synset {
+        Synset {
+            id: id.to_string(),
+            synset_id: id.to_string(),
+            language: "en".to_string(),
+            lexicon: "oewn".to_string(),
+            ili: None,
+            pos: "n".to_string(),
+            definition: None,
+            lemmas: vec![id.to_string()],
+            relations: relations
+                .iter()
+                .map(|(rel_type, target)| SynsetRelation {
+                    rel_type: rel_type.to_string(),
+                    target: target.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// a -> b -> c -> a is a hypernym cycle; a also has a duplicated edge to
+    /// b, a similar_to edge to d, and e reaches b by a second path.
+    fn fixture() -> HashMap<String, Synset> {
+        [
+            synset(
+                "a",
+                &[
+                    ("hypernym", "b"),
+                    ("hypernym", "b"),
+                    ("similar", "d"),
+                    ("instance_hypernym", "e"),
+                ],
+            ),
+            synset("b", &[("hypernym", "c")]),
+            synset("c", &[("hypernym", "a")]),
+            synset("d", &[]),
+            synset("e", &[("hypernym", "b")]),
+        ]
+        .into_iter()
+        .map(|s| (s.synset_id.clone(), s))
+        .collect()
+    }
+
+    async fn walk(roots: &[&str], relation: Option<&str>, depth: usize) -> RelationGraph {
+        let synsets = fixture();
+        let roots = roots.iter().map(|id| id.to_string()).collect();
+
+        walk_relation_graph("word", roots, relation, depth, |ids| {
+            let found: HashMap<String, Synset> = ids
+                .iter()
+                .filter_map(|id| synsets.get(id).map(|s| (id.clone(), s.clone())))
+                .collect();
+            async move { Ok(found) }
+        })
+        .await
+        .unwrap()
+    }
+
+    fn nodes(graph: &RelationGraph) -> Vec<(&str, usize)> {
+        graph
+            .nodes
+            .iter()
+            .map(|node| (node.synset_id.as_str(), node.depth))
+            .collect()
+    }
+
+    fn edges(graph: &RelationGraph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn walk_stops_at_the_requested_depth() {
+        let graph = walk(&["a"], Some("hypernym"), 1).await;
+        assert_eq!(nodes(&graph), vec![("a", 0), ("b", 1), ("e", 1)]);
+        assert_eq!(edges(&graph), vec![("a", "b"), ("a", "e")]);
+
+        let graph = walk(&["a"], Some("hypernym"), 2).await;
+        assert_eq!(nodes(&graph), vec![("a", 0), ("b", 1), ("e", 1), ("c", 2)]);
+
+        let graph = walk(&["a"], Some("hypernym"), 0).await;
+        assert_eq!(nodes(&graph), vec![("a", 0)]);
+        assert!(graph.edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn walk_ends_on_cycles() {
+        let graph = walk(&["a"], Some("hypernym"), MAX_GRAPH_DEPTH).await;
+
+        assert_eq!(nodes(&graph), vec![("a", 0), ("b", 1), ("e", 1), ("c", 2)]);
+        assert_eq!(
+            edges(&graph),
+            vec![("a", "b"), ("a", "e"), ("b", "c"), ("e", "b"), ("c", "a")]
+        );
+        assert!(!graph.truncated);
+    }
+
+    #[tokio::test]
+    async fn walk_keeps_each_node_and_edge_once() {
+        let graph = walk(&["a", "a", "b"], Some("hypernym"), 1).await;
+
+        assert_eq!(graph.roots, vec!["a", "b"]);
+        assert_eq!(nodes(&graph), vec![("a", 0), ("b", 0), ("e", 1), ("c", 1)]);
+        assert_eq!(edges(&graph), vec![("a", "b"), ("a", "e"), ("b", "c")]);
+    }
+
+    #[tokio::test]
+    async fn walk_follows_only_the_requested_relation() {
+        let graph = walk(&["a"], Some("similar"), 1).await;
+        assert_eq!(nodes(&graph), vec![("a", 0), ("d", 1)]);
+
+        let graph = walk(&["a"], None, 1).await;
+        assert_eq!(edges(&graph), vec![("a", "b"), ("a", "d"), ("a", "e")]);
+    }
+}
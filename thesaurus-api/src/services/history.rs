@@ -0,0 +1,138 @@
+use crate::{
+    error::AppError,
+    models::{history::HistoryEntry, user::User},
+};
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+const MAX_QUERY_LENGTH: usize = 255;
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub async fn list_history(
+    pool: &PgPool,
+    user_id: &Uuid,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    let entries = sqlx::query_as::<_, HistoryEntry>(
+        "SELECT id, query, created_at FROM search_history WHERE user_id = $1 \
+         ORDER BY created_at DESC OFFSET $2 LIMIT $3",
+    )
+    .bind(user_id)
+    .bind(offset)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+pub async fn delete_history_entry(
+    pool: &PgPool,
+    user_id: &Uuid,
+    entry_id: &Uuid,
+) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM search_history WHERE id = $1 AND user_id = $2")
+        .bind(entry_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "History entry '{}' not found",
+            entry_id
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn clear_history(pool: &PgPool, user_id: &Uuid) -> Result<u64, AppError> {
+    let result = sqlx::query("DELETE FROM search_history WHERE user_id = $1")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn set_history_paused(
+    pool: &PgPool,
+    user_id: &Uuid,
+    paused: bool,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE users SET history_paused = $1, updated_at = NOW() WHERE id = $2")
+        .bind(paused)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Records a lookup for `user` without holding up the response. Nothing is
+/// stored while the user has paused their history.
+pub fn record_lookup(pool: &PgPool, user: &User, query: &str) {
+    if user.history_paused {
+        return;
+    }
+
+    let query = query.trim();
+    if query.is_empty() {
+        return;
+    }
+
+    let pool = pool.clone();
+    let user_id = user.id;
+    let query: String = query.chars().take(MAX_QUERY_LENGTH).collect();
+
+    tokio::spawn(async move {
+        let result =
+            sqlx::query("INSERT INTO search_history (id, user_id, query) VALUES ($1, $2, $3)")
+                .bind(Uuid::new_v4())
+                .bind(user_id)
+                .bind(&query)
+                .execute(&pool)
+                .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to record search history: {}", e);
+        }
+    });
+}
+
+/// Deletes history older than `retention_days` every hour. A retention of
+/// zero keeps history forever.
+pub fn spawn_retention_task(pool: PgPool, retention_days: i64) {
+    if retention_days <= 0 {
+        tracing::info!("Search history retention disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let result = sqlx::query(
+                "DELETE FROM search_history WHERE created_at < NOW() - make_interval(days => $1)",
+            )
+            .bind(retention_days as i32)
+            .execute(&pool)
+            .await;
+
+            match result {
+                Ok(result) if result.rows_affected() > 0 => tracing::info!(
+                    "Deleted {} search history entries older than {} days",
+                    result.rows_affected(),
+                    retention_days
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to apply search history retention: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,181 @@
+use crate::{config::Config, error::AppError};
+use async_trait::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::{path::PathBuf, sync::Arc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), AppError>;
+}
+
+/// Picks the mailer named by `MAILER`: `smtp`, or `file` (the default).
+pub fn from_config(config: &Config) -> Result<Arc<dyn Mailer>, AppError> {
+    match config.mailer.as_str() {
+        "smtp" => Ok(Arc::new(SmtpMailer::new(config)?)),
+        "file" => Ok(Arc::new(FileMailer::new(config.mail_dir.as_ref()))),
+        other => Err(AppError::InternalServerError(format!(
+            "Unknown mailer '{}'",
+            other
+        ))),
+    }
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &Config) -> Result<Self, AppError> {
+        let host = config.smtp_host.as_deref().ok_or_else(|| {
+            AppError::InternalServerError("SMTP_HOST must be set for the smtp mailer".to_string())
+        })?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?
+            .port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let from = config
+            .mail_from
+            .parse()
+            .map_err(|e| AppError::InternalServerError(format!("Invalid MAIL_FROM: {}", e)))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), AppError> {
+        let to = email
+            .to
+            .parse()
+            .map_err(|e| AppError::InternalServerError(format!("Invalid recipient: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Writes each email to a file in `dir`, or only logs it when no directory
+/// is configured. Meant for development and tests.
+pub struct FileMailer {
+    dir: Option<PathBuf>,
+}
+
+impl FileMailer {
+    pub fn new(dir: Option<impl Into<PathBuf>>) -> Self {
+        Self {
+            dir: dir.map(Into::into),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), AppError> {
+        let Some(dir) = &self.dir else {
+            tracing::info!("Email to {} ({}):\n{}", email.to, email.subject, email.body);
+            return Ok(());
+        };
+
+        let path = dir.join(format!(
+            "{}-{}.eml",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S"),
+            Uuid::new_v4().simple()
+        ));
+        let contents = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            email.to, email.subject, email.body
+        );
+
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        tokio::fs::write(&path, contents)
+            .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+
+        tracing::info!("Wrote email to {} to {}", email.to, path.display());
+
+        Ok(())
+    }
+}
+
+/// Keeps every email it is asked to send, for tests to read back.
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingMailer {
+    pub sent: std::sync::Mutex<Vec<Email>>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, email: &Email) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(email.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_mailer_writes_the_message() {
+        let dir = std::env::temp_dir().join(format!("mailer-{}", Uuid::new_v4().simple()));
+        let mailer = FileMailer::new(Some(&dir));
+
+        mailer
+            .send(&Email {
+                to: "ada@example.com".to_string(),
+                subject: "Hello".to_string(),
+                body: "Line one\nLine two".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut entries = std::fs::read_dir(&dir).unwrap();
+        let path = entries.next().unwrap().unwrap().path();
+        assert!(entries.next().is_none());
+        assert_eq!(path.extension().unwrap(), "eml");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "To: ada@example.com\nSubject: Hello\n\nLine one\nLine two\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
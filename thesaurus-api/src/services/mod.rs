@@ -1,2 +1,22 @@
+pub mod account;
+pub mod api_keys;
 pub mod auth;
+pub mod autocomplete;
+pub mod favorites;
+pub mod graph;
+pub mod history;
+pub mod mailer;
+pub mod moderation;
+pub mod morphy;
+pub mod oidc;
+pub mod organizations;
+pub mod rate_limit;
 pub mod search;
+pub mod sessions;
+pub mod similarity;
+pub mod spellcheck;
+pub mod suggestions;
+pub mod tokens;
+pub mod translate;
+pub mod word_cache;
+pub mod word_lists;
@@ -0,0 +1,898 @@
+use crate::{
+    error::AppError,
+    models::{
+        proposal::{ModerationEvent, Proposal},
+        suggestion::Suggestion,
+        user::User,
+        word::{RelationType, Word, WordRelation},
+    },
+    services::search::{
+        fetch_word_by_exact_match, fetch_words_by_exact_match, relation_list_mut, word_cache,
+    },
+};
+use meilisearch_sdk::{
+    client::Client as MeiliClient,
+    errors::{Error as MeiliError, ErrorCode},
+};
+use serde::Deserialize;
+use sqlx::{PgPool, Postgres};
+use std::{collections::BTreeMap, time::Duration};
+use uuid::Uuid;
+
+const APPLY_INTERVAL: Duration = Duration::from_secs(30);
+const TASK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+/// The `rel_type` of typed relations added through proposals.
+const PROPOSAL_REL_TYPE: &str = "proposal";
+/// Words looked up per multi-search when checking applied proposals.
+const REQUEUE_BATCH_SIZE: usize = 100;
+
+/// Appends an entry to the moderation audit trail.
+async fn record_event<'e, E>(
+    executor: E,
+    subject_type: &str,
+    subject_id: &Uuid,
+    actor_id: Option<&Uuid>,
+    action: &str,
+    note: Option<&str>,
+) -> Result<(), AppError>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO moderation_events (id, subject_type, subject_id, actor_id, action, note) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(subject_type)
+    .bind(subject_id)
+    .bind(actor_id)
+    .bind(action)
+    .bind(note)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_events(
+    pool: &PgPool,
+    subject_type: &str,
+    subject_id: &Uuid,
+) -> Result<Vec<ModerationEvent>, AppError> {
+    let events = sqlx::query_as::<_, ModerationEvent>(
+        "SELECT * FROM moderation_events WHERE subject_type = $1 AND subject_id = $2 \
+         ORDER BY created_at",
+    )
+    .bind(subject_type)
+    .bind(subject_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_proposal(
+    pool: &PgPool,
+    user_id: &Uuid,
+    word: &str,
+    language: &str,
+    field: &str,
+    action: &str,
+    value: &str,
+    old_value: Option<&str>,
+    reason: Option<&str>,
+) -> Result<Proposal, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let proposal = sqlx::query_as::<_, Proposal>(
+        "INSERT INTO proposals (id, user_id, word, language, field, action, value, old_value, reason) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(word)
+    .bind(language)
+    .bind(field)
+    .bind(action)
+    .bind(value)
+    .bind(old_value)
+    .bind(reason)
+    .fetch_one(&mut tx)
+    .await?;
+
+    record_event(
+        &mut tx,
+        "proposal",
+        &proposal.id,
+        Some(user_id),
+        "submitted",
+        reason,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(proposal)
+}
+
+pub async fn list_user_proposals(pool: &PgPool, user_id: &Uuid) -> Result<Vec<Proposal>, AppError> {
+    let proposals = sqlx::query_as::<_, Proposal>(
+        "SELECT * FROM proposals WHERE user_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(proposals)
+}
+
+/// The moderation queue: proposals with `status`, oldest first.
+pub async fn list_proposals(
+    pool: &PgPool,
+    status: &str,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<Proposal>, AppError> {
+    let proposals = sqlx::query_as::<_, Proposal>(
+        "SELECT * FROM proposals WHERE status = $1 ORDER BY created_at OFFSET $2 LIMIT $3",
+    )
+    .bind(status)
+    .bind(offset)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(proposals)
+}
+
+pub async fn get_proposal(pool: &PgPool, proposal_id: &Uuid) -> Result<Proposal, AppError> {
+    sqlx::query_as::<_, Proposal>("SELECT * FROM proposals WHERE id = $1")
+        .bind(proposal_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Proposal '{}' not found", proposal_id)))
+}
+
+/// Approves or rejects a pending proposal. Approved proposals are picked up
+/// by [`spawn_apply_task`].
+pub async fn review_proposal(
+    pool: &PgPool,
+    reviewer_id: &Uuid,
+    proposal_id: &Uuid,
+    status: &str,
+    note: Option<&str>,
+) -> Result<Proposal, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let proposal = sqlx::query_as::<_, Proposal>(
+        "UPDATE proposals SET status = $3, reviewed_by = $2, reviewed_at = NOW(), updated_at = NOW() \
+         WHERE id = $1 AND status = 'pending' \
+         RETURNING *",
+    )
+    .bind(proposal_id)
+    .bind(reviewer_id)
+    .bind(status)
+    .fetch_optional(&mut tx)
+    .await?;
+
+    let Some(proposal) = proposal else {
+        return Err(not_pending(get_proposal(pool, proposal_id).await?.status));
+    };
+
+    record_event(
+        &mut tx,
+        "proposal",
+        proposal_id,
+        Some(reviewer_id),
+        status,
+        note,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(proposal)
+}
+
+/// Closes a pending proposal as a duplicate of `into`.
+pub async fn merge_proposal(
+    pool: &PgPool,
+    reviewer_id: &Uuid,
+    proposal_id: &Uuid,
+    into: &Uuid,
+    note: Option<&str>,
+) -> Result<Proposal, AppError> {
+    if proposal_id == into {
+        return Err(AppError::BadRequest(
+            "A proposal cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let source = get_proposal(pool, proposal_id).await?;
+    let target = get_proposal(pool, into).await?;
+
+    if target.status == "merged" || target.status == "rejected" {
+        return Err(AppError::BadRequest(format!(
+            "Cannot merge into a {} proposal",
+            target.status
+        )));
+    }
+    if source.word != target.word || source.language != target.language {
+        return Err(AppError::BadRequest(
+            "Only proposals for the same word can be merged".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let proposal = sqlx::query_as::<_, Proposal>(
+        "UPDATE proposals SET status = 'merged', merged_into = $3, reviewed_by = $2, \
+             reviewed_at = NOW(), updated_at = NOW() \
+         WHERE id = $1 AND status = 'pending' \
+         RETURNING *",
+    )
+    .bind(proposal_id)
+    .bind(reviewer_id)
+    .bind(into)
+    .fetch_optional(&mut tx)
+    .await?
+    .ok_or_else(|| not_pending(source.status))?;
+
+    let note = match note {
+        Some(note) => format!("Merged into {}: {}", into, note),
+        None => format!("Merged into {}", into),
+    };
+    record_event(
+        &mut tx,
+        "proposal",
+        proposal_id,
+        Some(reviewer_id),
+        "merged",
+        Some(&note),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(proposal)
+}
+
+fn not_pending(status: String) -> AppError {
+    AppError::BadRequest(format!("Already reviewed (status: {})", status))
+}
+
+/// Suggestions with `status`, oldest first, for moderators.
+pub async fn list_suggestions_for_review(
+    pool: &PgPool,
+    status: &str,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<Suggestion>, AppError> {
+    let suggestions = sqlx::query_as::<_, Suggestion>(
+        "SELECT *, 'community' AS provenance FROM suggestions WHERE status = $1 \
+         ORDER BY created_at OFFSET $2 LIMIT $3",
+    )
+    .bind(status)
+    .bind(offset)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suggestions)
+}
+
+/// Approves or rejects a pending suggestion. Approved suggestions are shown
+/// to everyone who opted into community suggestions.
+pub async fn review_suggestion(
+    pool: &PgPool,
+    reviewer_id: &Uuid,
+    suggestion_id: &Uuid,
+    status: &str,
+    note: Option<&str>,
+) -> Result<Suggestion, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let suggestion = sqlx::query_as::<_, Suggestion>(
+        "UPDATE suggestions SET status = $2, updated_at = NOW() \
+         WHERE id = $1 AND status = 'pending' \
+         RETURNING *, 'community' AS provenance",
+    )
+    .bind(suggestion_id)
+    .bind(status)
+    .fetch_optional(&mut tx)
+    .await?;
+
+    let Some(suggestion) = suggestion else {
+        let status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM suggestions WHERE id = $1")
+                .bind(suggestion_id)
+                .fetch_optional(pool)
+                .await?;
+
+        return Err(match status {
+            Some(status) => not_pending(status),
+            None => AppError::NotFound(format!("Suggestion '{}' not found", suggestion_id)),
+        });
+    };
+
+    record_event(
+        &mut tx,
+        "suggestion",
+        suggestion_id,
+        Some(reviewer_id),
+        status,
+        note,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(suggestion)
+}
+
+pub async fn set_user_role(
+    pool: &PgPool,
+    admin_id: &Uuid,
+    user_id: &Uuid,
+    role: &str,
+) -> Result<User, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user = sqlx::query_as::<_, User>(
+        "UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(user_id)
+    .bind(role)
+    .fetch_optional(&mut tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("User '{}' not found", user_id)))?;
+
+    record_event(
+        &mut tx,
+        "user",
+        user_id,
+        Some(admin_id),
+        "role_changed",
+        Some(role),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(user)
+}
+
+/// Makes the user signed up as `email` an admin (`ADMIN_EMAIL`), so a new
+/// install has someone who can hand out roles. Returns `None` if nobody has
+/// signed up with that address yet.
+pub async fn bootstrap_admin(pool: &PgPool, email: &str) -> Result<Option<User>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user =
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE lower(email) = lower($1) FOR UPDATE")
+            .bind(email)
+            .fetch_optional(&mut tx)
+            .await?;
+
+    let user = match user {
+        Some(user) if !user.is_admin() => {
+            let user = sqlx::query_as::<_, User>(
+                "UPDATE users SET role = 'admin', updated_at = NOW() WHERE id = $1 RETURNING *",
+            )
+            .bind(user.id)
+            .fetch_one(&mut tx)
+            .await?;
+
+            record_event(
+                &mut tx,
+                "user",
+                &user.id,
+                None,
+                "role_changed",
+                Some("admin"),
+            )
+            .await?;
+
+            Some(user)
+        }
+        user => user,
+    };
+
+    tx.commit().await?;
+
+    Ok(user)
+}
+
+fn field_mut<'a>(word: &'a mut Word, field: &str) -> Option<&'a mut Vec<String>> {
+    match field {
+        "definitions" => Some(&mut word.definitions),
+        "examples" => Some(&mut word.examples),
+        _ => RelationType::from_name(field).map(|rt| relation_list_mut(word, rt)),
+    }
+}
+
+/// Applies a proposal's edit to `values`. Adding a value that is already
+/// there, removing one that is not, or replacing with a value that is
+/// already in place is a no-op, so an edit can be applied again after an
+/// import.
+pub fn apply_edit(
+    values: &mut Vec<String>,
+    action: &str,
+    value: &str,
+    old_value: Option<&str>,
+) -> Result<(), String> {
+    match action {
+        "add" => {
+            if !values.iter().any(|v| v == value) {
+                values.push(value.to_string());
+            }
+            Ok(())
+        }
+        "remove" => {
+            values.retain(|v| v != value);
+            Ok(())
+        }
+        "replace" => {
+            let old_value = old_value.ok_or("Replacing needs an old value")?;
+            let has_value = values.iter().any(|v| v == value);
+
+            match values.iter().position(|v| v == old_value) {
+                Some(_) if has_value => values.retain(|v| v != old_value),
+                Some(position) => values[position] = value.to_string(),
+                None if has_value => {}
+                None => return Err(format!("'{}' is no longer present", old_value)),
+            }
+            Ok(())
+        }
+        _ => Err(format!("Unknown action '{}'", action)),
+    }
+}
+
+/// Brings the typed relations folded into `relation_type` in line with its
+/// edited list. A replaced word keeps its `rel_type`; words that are new to
+/// the list are added as [`PROPOSAL_REL_TYPE`].
+pub fn sync_relations(
+    word: &mut Word,
+    relation_type: RelationType,
+    action: &str,
+    value: &str,
+    old_value: Option<&str>,
+) {
+    let bucket = relation_type.as_str();
+    let listed = relation_list_mut(word, relation_type).clone();
+
+    if let (Some(old_value), "replace") = (old_value, action) {
+        for relation in word.relations.iter_mut() {
+            if relation.bucket == bucket && relation.word == old_value {
+                relation.word = value.to_string();
+            }
+        }
+    }
+
+    let mut kept: Vec<WordRelation> = Vec::with_capacity(word.relations.len());
+    for relation in word.relations.drain(..) {
+        if (relation.bucket != bucket || listed.contains(&relation.word))
+            && !kept.contains(&relation)
+        {
+            kept.push(relation);
+        }
+    }
+    word.relations = kept;
+
+    for listed_word in listed {
+        let present = word
+            .relations
+            .iter()
+            .any(|relation| relation.bucket == bucket && relation.word == listed_word);
+
+        if !present {
+            word.relations.push(WordRelation {
+                rel_type: PROPOSAL_REL_TYPE.to_string(),
+                bucket: bucket.to_string(),
+                word: listed_word,
+            });
+        }
+    }
+}
+
+/// Why a proposal could not be written.
+enum ApplyError {
+    /// Meilisearch did not finish the write in time. It may still land, so
+    /// the proposal is tried again on the next run.
+    TimedOut,
+    Failed(String),
+}
+
+impl From<String> for ApplyError {
+    fn from(e: String) -> Self {
+        ApplyError::Failed(e)
+    }
+}
+
+/// Writes an approved proposal into its `words` document. Only the edited
+/// field, its typed relations and the list of applied proposals are sent,
+/// so the rest of the document is left alone.
+async fn write_proposal(meili: &MeiliClient, proposal: &Proposal) -> Result<(), ApplyError> {
+    let mut word = fetch_word_by_exact_match(meili, &proposal.word, &proposal.language)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Word '{}' not found", proposal.word))?;
+
+    let values = field_mut(&mut word, &proposal.field)
+        .ok_or_else(|| format!("Unknown field '{}'", proposal.field))?;
+
+    apply_edit(
+        values,
+        &proposal.action,
+        &proposal.value,
+        proposal.old_value.as_deref(),
+    )?;
+
+    let mut document = serde_json::Map::new();
+    document.insert(proposal.field.clone(), values.clone().into());
+    document.insert("id".to_string(), word.id.clone().into());
+
+    if let Some(relation_type) = RelationType::from_name(&proposal.field) {
+        sync_relations(
+            &mut word,
+            relation_type,
+            &proposal.action,
+            &proposal.value,
+            proposal.old_value.as_deref(),
+        );
+
+        let relations = serde_json::to_value(&word.relations).map_err(|e| e.to_string())?;
+        document.insert("relations".to_string(), relations);
+    }
+
+    let proposal_id = proposal.id.to_string();
+    if !word.applied_proposals.contains(&proposal_id) {
+        word.applied_proposals.push(proposal_id);
+    }
+    document.insert(
+        "applied_proposals".to_string(),
+        word.applied_proposals.into(),
+    );
+
+    let task = meili
+        .index("words")
+        .add_or_update(&[document], Some("id"))
+        .await
+        .map_err(|e| e.to_string())?
+        .wait_for_completion(meili, Some(TASK_POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await
+        .map_err(|e| match e {
+            MeiliError::Timeout => ApplyError::TimedOut,
+            e => ApplyError::Failed(e.to_string()),
+        })?;
+
+    if task.is_failure() {
+        return Err(ApplyError::Failed(task.unwrap_failure().to_string()));
+    }
+
+    if let Some(cache) = word_cache() {
+        cache.invalidate(&proposal.word, &proposal.language);
+    }
+
+    Ok(())
+}
+
+/// The marker `wordnet-importer` keeps in the `imports` index for each
+/// index it imports into.
+#[derive(Debug, Deserialize)]
+struct ImportMarker {
+    imported_at: u64,
+}
+
+/// When the `words` index was last imported, or `None` if no import has
+/// left a marker.
+async fn last_import(meili: &MeiliClient) -> Result<Option<u64>, MeiliError> {
+    match meili
+        .index("imports")
+        .get_document::<ImportMarker>("words")
+        .await
+    {
+        Ok(marker) => Ok(Some(marker.imported_at)),
+        Err(MeiliError::Meilisearch(e))
+            if matches!(
+                e.error_code,
+                ErrorCode::IndexNotFound | ErrorCode::DocumentNotFound
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Finds applied proposals whose edit is gone from the `words` index, as
+/// happens when an import or index swap replaces the documents, and clears
+/// their `applied_at` so [`apply_approved_proposals`] writes them again in
+/// review order. Each document lists the proposals written into it.
+async fn requeue_lost_proposals(pool: &PgPool, meili: &MeiliClient) -> Result<usize, AppError> {
+    let applied = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT id, word, language FROM proposals \
+         WHERE status = 'approved' AND applied_at IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_language: BTreeMap<String, BTreeMap<String, Vec<Uuid>>> = BTreeMap::new();
+    for (id, word, language) in applied {
+        by_language
+            .entry(language)
+            .or_default()
+            .entry(word)
+            .or_default()
+            .push(id);
+    }
+
+    let mut lost: Vec<Uuid> = Vec::new();
+
+    for (language, words) in by_language {
+        let words: Vec<(String, Vec<Uuid>)> = words.into_iter().collect();
+
+        for batch in words.chunks(REQUEUE_BATCH_SIZE) {
+            let names: Vec<String> = batch.iter().map(|(word, _)| word.clone()).collect();
+            let documents = fetch_words_by_exact_match(meili, &names, &language).await?;
+
+            for ((_, ids), document) in batch.iter().zip(documents) {
+                let written = document
+                    .map(|document| document.applied_proposals)
+                    .unwrap_or_default();
+
+                lost.extend(ids.iter().filter(|id| !written.contains(&id.to_string())));
+            }
+        }
+    }
+
+    if lost.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let requeued = sqlx::query_scalar::<_, Uuid>(
+        "UPDATE proposals SET applied_at = NULL, updated_at = NOW() \
+         WHERE id = ANY($1) AND applied_at IS NOT NULL \
+         RETURNING id",
+    )
+    .bind(&lost)
+    .fetch_all(&mut tx)
+    .await?;
+
+    for id in &requeued {
+        record_event(
+            &mut tx,
+            "proposal",
+            id,
+            None,
+            "requeued",
+            Some("Missing from the words index"),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(requeued.len())
+}
+
+/// Writes approved proposals to Meilisearch one at a time. Each proposal is
+/// claimed by setting `applied_at` first, so several API instances can run
+/// this side by side. Failures are kept in `apply_error` and not retried;
+/// a write that times out is released and the run ends, to be picked up
+/// again next time.
+async fn apply_approved_proposals(pool: &PgPool, meili: &MeiliClient) -> Result<usize, AppError> {
+    let mut applied = 0;
+
+    loop {
+        let proposal = sqlx::query_as::<_, Proposal>(
+            "UPDATE proposals SET applied_at = NOW(), updated_at = NOW() \
+             WHERE id = ( \
+                 SELECT id FROM proposals \
+                 WHERE status = 'approved' AND applied_at IS NULL AND apply_error IS NULL \
+                 ORDER BY reviewed_at LIMIT 1 FOR UPDATE SKIP LOCKED) \
+             RETURNING *",
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(proposal) = proposal else {
+            return Ok(applied);
+        };
+
+        match write_proposal(meili, &proposal).await {
+            Ok(()) => {
+                record_event(pool, "proposal", &proposal.id, None, "applied", None).await?;
+                applied += 1;
+            }
+            Err(ApplyError::TimedOut) => {
+                tracing::warn!("Applying proposal {} timed out, will retry", proposal.id);
+
+                sqlx::query(
+                    "UPDATE proposals SET applied_at = NULL, updated_at = NOW() WHERE id = $1",
+                )
+                .bind(proposal.id)
+                .execute(pool)
+                .await?;
+
+                return Ok(applied);
+            }
+            Err(ApplyError::Failed(e)) => {
+                tracing::warn!("Failed to apply proposal {}: {}", proposal.id, e);
+
+                sqlx::query(
+                    "UPDATE proposals SET applied_at = NULL, apply_error = $2, updated_at = NOW() \
+                     WHERE id = $1",
+                )
+                .bind(proposal.id)
+                .bind(&e)
+                .execute(pool)
+                .await?;
+
+                record_event(
+                    pool,
+                    "proposal",
+                    &proposal.id,
+                    None,
+                    "apply_failed",
+                    Some(&e),
+                )
+                .await?;
+            }
+        }
+    }
+}
+
+/// Writes newly approved proposals into the `words` index every
+/// [`APPLY_INTERVAL`]. At startup and after every import, proposals the
+/// import threw away are queued up again first.
+pub fn spawn_apply_task(pool: PgPool, meili: MeiliClient) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(APPLY_INTERVAL);
+        let mut checked_import = None;
+
+        loop {
+            interval.tick().await;
+
+            match last_import(&meili).await {
+                Ok(import) if checked_import != Some(import) => {
+                    match requeue_lost_proposals(&pool, &meili).await {
+                        Ok(requeued) => {
+                            if requeued > 0 {
+                                tracing::info!("Requeued {} proposals lost to an import", requeued);
+                            }
+                            checked_import = Some(import);
+                        }
+                        Err(e) => tracing::error!("Failed to check applied proposals: {}", e),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to fetch the last import marker: {}", e),
+            }
+
+            match apply_approved_proposals(&pool, &meili).await {
+                Ok(applied) if applied > 0 => {
+                    tracing::info!("Applied {} approved proposals", applied)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to apply approved proposals: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word() -> Word {
+        serde_json::from_value(serde_json::json!({
+            "id": "word_en_bank",
+            "word": "bank",
+            "language": "en",
+            "lexicon": "oewn",
+            "definitions": [],
+            "pos": ["n"],
+            "synonyms": ["depository"],
+            "antonyms": [],
+            "broader_terms": ["slope"],
+            "narrower_terms": [],
+            "related_terms": [],
+            "relations": [
+                { "rel_type": "similar", "bucket": "synonyms", "word": "depository" },
+                { "rel_type": "hypernym", "bucket": "broader_terms", "word": "slope" }
+            ],
+            "examples": []
+        }))
+        .unwrap()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn apply_edit_replaces_in_place() {
+        let mut values = strings(&["a", "b", "c"]);
+
+        apply_edit(&mut values, "replace", "x", Some("b")).unwrap();
+
+        assert_eq!(values, strings(&["a", "x", "c"]));
+    }
+
+    #[test]
+    fn apply_edit_can_be_applied_again() {
+        let mut values = strings(&["a", "b"]);
+
+        apply_edit(&mut values, "add", "c", None).unwrap();
+        apply_edit(&mut values, "add", "c", None).unwrap();
+        apply_edit(&mut values, "replace", "x", Some("a")).unwrap();
+        apply_edit(&mut values, "replace", "x", Some("a")).unwrap();
+
+        assert_eq!(values, strings(&["x", "b", "c"]));
+    }
+
+    #[test]
+    fn apply_edit_replace_drops_the_old_value_if_the_new_one_is_listed() {
+        let mut values = strings(&["a", "b"]);
+
+        apply_edit(&mut values, "replace", "b", Some("a")).unwrap();
+
+        assert_eq!(values, strings(&["b"]));
+    }
+
+    #[test]
+    fn apply_edit_replace_fails_if_neither_value_is_present() {
+        let mut values = strings(&["a"]);
+
+        assert!(apply_edit(&mut values, "replace", "x", Some("b")).is_err());
+        assert!(apply_edit(&mut values, "replace", "x", None).is_err());
+    }
+
+    #[test]
+    fn sync_relations_adds_and_removes_typed_relations() {
+        let mut word = word();
+        word.synonyms = strings(&["bluff"]);
+
+        sync_relations(&mut word, RelationType::Synonym, "add", "bluff", None);
+
+        assert_eq!(
+            word.relations,
+            vec![
+                WordRelation {
+                    rel_type: "hypernym".to_string(),
+                    bucket: "broader_terms".to_string(),
+                    word: "slope".to_string(),
+                },
+                WordRelation {
+                    rel_type: PROPOSAL_REL_TYPE.to_string(),
+                    bucket: "synonyms".to_string(),
+                    word: "bluff".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sync_relations_keeps_the_rel_type_of_a_replaced_word() {
+        let mut word = word();
+        word.broader_terms = strings(&["incline"]);
+
+        sync_relations(
+            &mut word,
+            RelationType::BroaderTerm,
+            "replace",
+            "incline",
+            Some("slope"),
+        );
+
+        assert_eq!(word.relations[1].rel_type, "hypernym");
+        assert_eq!(word.relations[1].word, "incline");
+        assert_eq!(word.relations.len(), 2);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::services::autocomplete::{self, Autocomplete};
+use serde::Serialize;
+
+/// WordNet's morphy detachment rules: an inflectional suffix and what
+/// replaces it, per part of speech.
+const NOUN_RULES: &[(&str, &str)] = &[
+    ("s", ""),
+    ("ses", "s"),
+    ("xes", "x"),
+    ("zes", "z"),
+    ("ches", "ch"),
+    ("shes", "sh"),
+    ("men", "man"),
+    ("ies", "y"),
+];
+const VERB_RULES: &[(&str, &str)] = &[
+    ("s", ""),
+    ("ies", "y"),
+    ("es", "e"),
+    ("es", ""),
+    ("ed", "e"),
+    ("ed", ""),
+    ("ing", "e"),
+    ("ing", ""),
+];
+const ADJECTIVE_RULES: &[(&str, &str)] = &[("er", ""), ("est", ""), ("er", "e"), ("est", "e")];
+
+const RULES: [(&str, &[(&str, &str)]); 3] =
+    [("n", NOUN_RULES), ("v", VERB_RULES), ("a", ADJECTIVE_RULES)];
+
+/// How an inflected word was mapped to the lemma that was looked up.
+#[derive(Debug, Clone, Serialize)]
+pub struct Resolution {
+    pub inflected: String,
+    pub lemma: String,
+    pub pos: Option<String>,
+}
+
+/// A lemma that `form` may be an inflection of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub lemma: String,
+    pub pos: Option<String>,
+}
+
+/// Candidate lemmas for `form`, best first. Irregular forms listed in the
+/// lexicon (LMF `Form` elements, e.g. "geese") come first, then morphy's
+/// suffix rules for English. Only lemmas that exist with the rule's part
+/// of speech are kept.
+pub fn analyze(autocomplete: &Autocomplete, lang: &str, form: &str) -> Vec<Candidate> {
+    let form = normalize(form);
+    let mut candidates = Vec::new();
+
+    for completion in autocomplete.lemmas_with_form(lang, &form) {
+        let pos = match completion.pos.as_slice() {
+            [pos] => Some(pos.clone()),
+            _ => None,
+        };
+        push_candidate(&mut candidates, &completion.word, pos);
+    }
+
+    if lang != "en" {
+        return candidates;
+    }
+
+    for (pos, rules) in RULES {
+        for (suffix, ending) in rules {
+            let Some(stem) = form.strip_suffix(suffix) else {
+                continue;
+            };
+            if stem.is_empty() {
+                continue;
+            }
+
+            let mut bases = vec![format!("{}{}", stem, ending)];
+            // "running" -> "run", "bigger" -> "big"
+            if ending.is_empty() && matches!(*suffix, "ing" | "ed" | "er" | "est") {
+                bases.extend(undouble(stem));
+            }
+
+            for base in bases {
+                for completion in autocomplete.lemma(lang, &base).into_iter().flatten() {
+                    if has_pos(&completion.pos, pos) {
+                        push_candidate(&mut candidates, &completion.word, Some(pos.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// The lemma to look up for `word`, if it is an inflection rather than a
+/// lemma itself. `None` when autocomplete has not been built yet.
+pub fn resolve(lang: &str, word: &str) -> Option<Resolution> {
+    let autocomplete = autocomplete::current()?;
+    let form = normalize(word);
+
+    if autocomplete.contains(lang, &form) {
+        return None;
+    }
+
+    let candidate = analyze(&autocomplete, lang, &form).into_iter().next()?;
+
+    Some(Resolution {
+        inflected: word.to_string(),
+        lemma: candidate.lemma,
+        pos: candidate.pos,
+    })
+}
+
+fn normalize(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+fn push_candidate(candidates: &mut Vec<Candidate>, lemma: &str, pos: Option<String>) {
+    if !candidates.iter().any(|c| c.lemma == lemma) {
+        candidates.push(Candidate {
+            lemma: lemma.to_string(),
+            pos,
+        });
+    }
+}
+
+/// Adjective satellites (`s`) count as adjectives. Lemmas without POS
+/// information match any rule.
+fn has_pos(lemma_pos: &[String], pos: &str) -> bool {
+    lemma_pos.is_empty()
+        || lemma_pos
+            .iter()
+            .any(|p| p == pos || (pos == "a" && p == "s"))
+}
+
+/// `stem` without its last letter, if that letter is a doubled consonant.
+fn undouble(stem: &str) -> Option<String> {
+    let mut chars = stem.chars().rev();
+    let (last, before) = (chars.next()?, chars.next()?);
+
+    (last == before && !"aeiou".contains(last))
+        .then(|| stem[..stem.len() - last.len_utf8()].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn autocomplete() -> Autocomplete {
+        Autocomplete::from_documents(json!([
+            { "word": "goose", "language": "en", "pos": ["n"], "forms": ["geese"] },
+            { "word": "run", "language": "en", "pos": ["v", "n"], "forms": ["ran"] },
+            { "word": "big", "language": "en", "pos": ["a"] },
+            { "word": "red", "language": "en", "pos": ["s"] },
+            { "word": "box", "language": "en", "pos": ["n", "v"] },
+            { "word": "make", "language": "en", "pos": ["v"] },
+            { "word": "fly", "language": "en", "pos": ["n", "v"] },
+            { "word": "bank", "language": "en", "pos": ["n"] },
+            { "word": "oie", "language": "fr", "pos": ["n"], "forms": ["oies"] },
+            { "word": "chat", "language": "fr", "pos": ["n"] },
+        ]))
+    }
+
+    fn lemmas(lang: &str, form: &str) -> Vec<(String, Option<String>)> {
+        analyze(&autocomplete(), lang, form)
+            .into_iter()
+            .map(|c| (c.lemma, c.pos))
+            .collect()
+    }
+
+    fn candidate(lemma: &str, pos: Option<&str>) -> (String, Option<String>) {
+        (lemma.to_string(), pos.map(str::to_string))
+    }
+
+    #[test]
+    fn analyze_uses_lexicon_forms_first() {
+        assert_eq!(lemmas("en", "geese"), vec![candidate("goose", Some("n"))]);
+        // Lemmas with several parts of speech leave the POS open
+        assert_eq!(lemmas("en", "ran"), vec![candidate("run", None)]);
+    }
+
+    #[test]
+    fn analyze_applies_suffix_rules() {
+        assert_eq!(lemmas("en", "banks"), vec![candidate("bank", Some("n"))]);
+        assert_eq!(lemmas("en", "boxes"), vec![candidate("box", Some("n"))]);
+        assert_eq!(lemmas("en", "flies"), vec![candidate("fly", Some("n"))]);
+        assert_eq!(lemmas("en", "making"), vec![candidate("make", Some("v"))]);
+        assert_eq!(lemmas("en", "running"), vec![candidate("run", Some("v"))]);
+        assert_eq!(lemmas("en", "bigger"), vec![candidate("big", Some("a"))]);
+        assert_eq!(lemmas("en", "reddest"), vec![candidate("red", Some("a"))]);
+    }
+
+    #[test]
+    fn analyze_normalizes_input() {
+        assert_eq!(lemmas("en", " Banks "), vec![candidate("bank", Some("n"))]);
+    }
+
+    #[test]
+    fn analyze_checks_lemma_pos() {
+        // "bank" is only a noun, so the adjective rule "-er" does not apply
+        assert!(lemmas("en", "banker").is_empty());
+        // "make" is not a noun or adjective
+        assert!(lemmas("en", "maker").is_empty());
+        assert!(lemmas("en", "zzz").is_empty());
+    }
+
+    #[test]
+    fn analyze_only_applies_suffix_rules_to_english() {
+        assert_eq!(lemmas("fr", "oies"), vec![candidate("oie", Some("n"))]);
+        assert!(lemmas("fr", "chats").is_empty());
+    }
+
+    #[test]
+    fn undouble_drops_doubled_consonants() {
+        assert_eq!(undouble("runn").as_deref(), Some("run"));
+        assert_eq!(undouble("bigg").as_deref(), Some("big"));
+        assert_eq!(undouble("see"), None);
+        assert_eq!(undouble("run"), None);
+        assert_eq!(undouble("r"), None);
+    }
+
+    #[test]
+    fn has_pos_treats_satellites_as_adjectives() {
+        let pos = |values: &[&str]| values.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        assert!(has_pos(&pos(&["n", "v"]), "v"));
+        assert!(has_pos(&pos(&["s"]), "a"));
+        assert!(!has_pos(&pos(&["a"]), "s"));
+        assert!(!has_pos(&pos(&["n"]), "v"));
+        assert!(has_pos(&[], "n"));
+    }
+}
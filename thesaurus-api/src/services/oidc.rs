@@ -0,0 +1,631 @@
+use crate::{
+    config::Config,
+    error::AppError,
+    models::{
+        oidc::{IdTokenClaims, LoginState, OidcTokenResponse, ProviderMetadata},
+        user::User,
+    },
+    services::tokens::generate_opaque_token,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use url::Url;
+use uuid::Uuid;
+
+const METADATA_TTL: Duration = Duration::from_secs(60 * 60);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+pub const LOGIN_STATE_TTL_MINUTES: i32 = 10;
+
+struct Provider {
+    metadata: ProviderMetadata,
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+fn provider_error(e: impl std::fmt::Display) -> AppError {
+    AppError::InternalServerError(format!("Identity provider error: {}", e))
+}
+
+/// A relying party for one OpenID Connect provider. Discovery metadata and
+/// signing keys are fetched lazily and cached for an hour; an unknown key id
+/// triggers an early refresh so key rotation is picked up.
+pub struct OidcClient {
+    http: reqwest::Client,
+    issuer: String,
+    client_id: String,
+    client_secret: Option<String>,
+    redirect_uri: String,
+    scopes: String,
+    provider: RwLock<Option<Arc<Provider>>>,
+}
+
+impl OidcClient {
+    /// Set up when `OIDC_ISSUER`, `OIDC_CLIENT_ID` and `OIDC_REDIRECT_URI`
+    /// are all configured. `OIDC_CLIENT_SECRET` is optional for public
+    /// clients, which rely on PKCE alone.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        let (Some(issuer), Some(client_id), Some(redirect_uri)) = (
+            &config.oidc_issuer,
+            &config.oidc_client_id,
+            &config.oidc_redirect_uri,
+        ) else {
+            return None;
+        };
+
+        Some(Arc::new(Self::new(
+            issuer,
+            client_id,
+            config.oidc_client_secret.clone(),
+            redirect_uri,
+            &config.oidc_scopes,
+        )))
+    }
+
+    fn new(
+        issuer: &str,
+        client_id: &str,
+        client_secret: Option<String>,
+        redirect_uri: &str,
+        scopes: &str,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http,
+            issuer: issuer.trim_end_matches('/').to_string(),
+            client_id: client_id.to_string(),
+            client_secret,
+            redirect_uri: redirect_uri.to_string(),
+            scopes: scopes.to_string(),
+            provider: RwLock::new(None),
+        }
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, AppError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)
+    }
+
+    async fn provider(&self, refresh: bool) -> Result<Arc<Provider>, AppError> {
+        if !refresh {
+            if let Some(provider) = self.provider.read().await.as_ref() {
+                if provider.fetched_at.elapsed() < METADATA_TTL {
+                    return Ok(provider.clone());
+                }
+            }
+        }
+
+        let metadata: ProviderMetadata = self
+            .get_json(&format!("{}/.well-known/openid-configuration", self.issuer))
+            .await?;
+
+        if metadata.issuer.trim_end_matches('/') != self.issuer {
+            return Err(provider_error(format!(
+                "discovery document is for issuer '{}'",
+                metadata.issuer
+            )));
+        }
+
+        let jwks: JwkSet = self.get_json(&metadata.jwks_uri).await?;
+
+        let provider = Arc::new(Provider {
+            metadata,
+            jwks,
+            fetched_at: Instant::now(),
+        });
+        *self.provider.write().await = Some(provider.clone());
+
+        Ok(provider)
+    }
+
+    /// Where to send the browser to sign in.
+    pub async fn authorization_url(&self, login: &LoginState) -> Result<String, AppError> {
+        let provider = self.provider(false).await?;
+
+        let mut url =
+            Url::parse(&provider.metadata.authorization_endpoint).map_err(provider_error)?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &self.scopes)
+            .append_pair("state", &login.state)
+            .append_pair("nonce", &login.nonce)
+            .append_pair("code_challenge", &pkce_challenge(&login.code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url.into())
+    }
+
+    /// Redeems an authorization code and returns the verified ID token claims.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        login: &LoginState,
+    ) -> Result<IdTokenClaims, AppError> {
+        let provider = self.provider(false).await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_uri),
+            ("client_id", &self.client_id),
+            ("code_verifier", &login.code_verifier),
+        ];
+        if let Some(client_secret) = &self.client_secret {
+            form.push(("client_secret", client_secret));
+        }
+
+        let response: OidcTokenResponse = self
+            .http
+            .post(&provider.metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)?;
+
+        self.verify_id_token(&response.id_token, &login.nonce).await
+    }
+
+    async fn decoding_key(
+        &self,
+        refresh: bool,
+        kid: Option<&str>,
+    ) -> Result<Option<(DecodingKey, String)>, AppError> {
+        let provider = self.provider(refresh).await?;
+
+        let jwk = match kid {
+            Some(kid) => provider.jwks.find(kid),
+            None if provider.jwks.keys.len() == 1 => provider.jwks.keys.first(),
+            None => None,
+        };
+
+        match jwk {
+            Some(jwk) => Ok(Some((
+                DecodingKey::from_jwk(jwk)?,
+                provider.metadata.issuer.clone(),
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, AppError> {
+        let invalid =
+            |e: &dyn std::fmt::Display| AppError::BadRequest(format!("Invalid ID token: {}", e));
+
+        let header = decode_header(id_token).map_err(|e| invalid(&e))?;
+
+        // Symmetric algorithms would let anyone holding the client secret mint tokens.
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(invalid(&"unsupported signing algorithm"));
+        }
+
+        let kid = header.kid.as_deref();
+        let (key, issuer) = match self.decoding_key(false, kid).await? {
+            Some(found) => found,
+            None => self
+                .decoding_key(true, kid)
+                .await?
+                .ok_or_else(|| invalid(&"unknown signing key"))?,
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_issuer(&[&issuer]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| invalid(&e))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid(&"nonce mismatch"));
+        }
+
+        Ok(claims)
+    }
+}
+
+/// The S256 PKCE challenge for `verifier`.
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Starts a login: stores a fresh state, nonce and PKCE verifier.
+pub async fn save_login_state(pool: &PgPool, redirect_to: &str) -> Result<LoginState, AppError> {
+    sqlx::query("DELETE FROM oidc_login_states WHERE expires_at < NOW()")
+        .execute(pool)
+        .await?;
+
+    let login = sqlx::query_as::<_, LoginState>(
+        "INSERT INTO oidc_login_states (state, code_verifier, nonce, redirect_to, expires_at) \
+         VALUES ($1, $2, $3, $4, NOW() + make_interval(mins => $5)) \
+         RETURNING state, code_verifier, nonce, redirect_to",
+    )
+    .bind(generate_opaque_token())
+    .bind(generate_opaque_token())
+    .bind(generate_opaque_token())
+    .bind(redirect_to)
+    .bind(LOGIN_STATE_TTL_MINUTES)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(login)
+}
+
+/// Looks up and removes the login started with `state`, so every state can
+/// only be used once.
+pub async fn take_login_state(pool: &PgPool, state: &str) -> Result<LoginState, AppError> {
+    sqlx::query_as::<_, LoginState>(
+        "DELETE FROM oidc_login_states WHERE state = $1 AND expires_at > NOW() \
+         RETURNING state, code_verifier, nonce, redirect_to",
+    )
+    .bind(state)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Unknown or expired login state".to_string()))
+}
+
+/// The user linked to this identity. New identities are linked to an
+/// existing account with the same email only if the provider has verified
+/// that email; otherwise a new account without a password is created.
+///
+/// If the existing account never verified its email, whoever set its
+/// password may not own the address, so linking drops the password and
+/// revokes every session and API key.
+pub async fn find_or_create_user(
+    pool: &PgPool,
+    issuer: &str,
+    claims: &IdTokenClaims,
+) -> Result<User, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let linked = sqlx::query_as::<_, User>(
+        "SELECT u.* FROM user_identities i JOIN users u ON u.id = i.user_id \
+         WHERE i.issuer = $1 AND i.subject = $2",
+    )
+    .bind(issuer)
+    .bind(&claims.sub)
+    .fetch_optional(&mut tx)
+    .await?;
+
+    if let Some(user) = linked {
+        sqlx::query(
+            "UPDATE user_identities SET last_login_at = NOW(), email = COALESCE($3, email) \
+             WHERE issuer = $1 AND subject = $2",
+        )
+        .bind(issuer)
+        .bind(&claims.sub)
+        .bind(&claims.email)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        return Ok(user);
+    }
+
+    let email = claims.email.as_deref().ok_or_else(|| {
+        AppError::BadRequest("The identity provider did not share an email address".to_string())
+    })?;
+
+    let existing = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(&mut tx)
+        .await?;
+
+    let user = match existing {
+        Some(_) if !claims.email_verified => return Err(AppError::UserAlreadyExists),
+        Some(user) if user.email_verified_at.is_some() => user,
+        Some(user) => {
+            sqlx::query(
+                "UPDATE user_sessions SET revoked_at = NOW() \
+                 WHERE user_id = $1 AND revoked_at IS NULL",
+            )
+            .bind(user.id)
+            .execute(&mut tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE api_keys SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+            )
+            .bind(user.id)
+            .execute(&mut tx)
+            .await?;
+
+            sqlx::query_as::<_, User>(
+                "UPDATE users SET password = NULL, email_verified_at = NOW(), updated_at = NOW() \
+                 WHERE id = $1 RETURNING *",
+            )
+            .bind(user.id)
+            .fetch_one(&mut tx)
+            .await?
+        }
+        None => {
+            let name = claims
+                .name
+                .as_deref()
+                .or(claims.preferred_username.as_deref())
+                .unwrap_or(email);
+
+            sqlx::query_as::<_, User>(
+                "INSERT INTO users (id, name, email, password, email_verified_at) \
+                 VALUES ($1, $2, $3, NULL, CASE WHEN $4 THEN NOW() END) \
+                 RETURNING *",
+            )
+            .bind(Uuid::new_v4())
+            .bind(name)
+            .bind(email)
+            .bind(claims.email_verified)
+            .fetch_one(&mut tx)
+            .await?
+        }
+    };
+
+    sqlx::query(
+        "INSERT INTO user_identities (id, user_id, issuer, subject, email) \
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.id)
+    .bind(issuer)
+    .bind(&claims.sub)
+    .bind(email)
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::State,
+        http::StatusCode,
+        routing::{get, post},
+        Form, Json, Router,
+    };
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::{json, Value};
+    use std::{collections::HashMap, net::TcpListener};
+
+    const SIGNING_KEY: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/oidc-signing-key.pem"
+    ));
+    /// The modulus of `SIGNING_KEY`, base64url encoded for the JWKS.
+    const MODULUS: &str = "s2YnKVZAm7zyX3o_Ofq4AF8E1jQOI4DYNyMgF1YEZkVDI9WuHfpXr35BAx0lDWgRsCyZwJLofiMKCzSUmTPiqWn7VlTakGAFry4j_pleXUIVfUbDPcB24eHQQEur_SnnGXKx0-mffYt0HGtKNuJ0-4HZJ0jGb0pLwtSizgEkUi3EbxbkHVs8UGy9uzqzylnfMrVIdF4gXRQ8WHw_R1mxqM8YikMwv5g5o-rktZE5-jnbV5dg1iI2XbM4IyMpQVRDd5D-ipKar1TU6RAGJHVqSvpv2OBr0xX-sphHkt8IGGkV0UDUnc0lqjfu4AhkDIHXcCi__H7ASvKGn9eKJY_OmQ";
+    const KEY_ID: &str = "test-key";
+    const CLIENT_ID: &str = "thesaurus";
+    const NONCE: &str = "n-0S6_WzA2Mj";
+    const CODE: &str = "good-code";
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    fn rs256() -> Header {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(KEY_ID.to_string());
+        header
+    }
+
+    fn sign(header: &Header, claims: &Value) -> String {
+        let key = EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).unwrap();
+        encode(header, claims, &key).unwrap()
+    }
+
+    fn claims(issuer: &str) -> Value {
+        json!({
+            "iss": issuer,
+            "aud": CLIENT_ID,
+            "sub": "user-1",
+            "nonce": NONCE,
+            "email": "ada@example.com",
+            "email_verified": true,
+            "exp": chrono::Utc::now().timestamp() + 600,
+        })
+    }
+
+    async fn discovery(State(issuer): State<String>) -> Json<Value> {
+        Json(json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+        }))
+    }
+
+    async fn jwks() -> Json<Value> {
+        Json(json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": KEY_ID,
+                "use": "sig",
+                "alg": "RS256",
+                "n": MODULUS,
+                "e": "AQAB",
+            }]
+        }))
+    }
+
+    /// Hands out an ID token for [`CODE`], but only with the right PKCE
+    /// verifier, as a real provider would.
+    async fn token(
+        State(issuer): State<String>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<Value>, StatusCode> {
+        let field = |name: &str| form.get(name).map(String::as_str);
+
+        if field("grant_type") != Some("authorization_code")
+            || field("code") != Some(CODE)
+            || field("code_verifier") != Some(VERIFIER)
+            || field("client_id") != Some(CLIENT_ID)
+        {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        Ok(Json(json!({
+            "id_token": sign(&rs256(), &claims(&issuer)),
+            "token_type": "Bearer",
+        })))
+    }
+
+    /// Starts a mock issuer on a free local port and returns its URL.
+    fn mock_issuer() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(issuer.clone());
+
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        issuer
+    }
+
+    fn client(issuer: &str) -> OidcClient {
+        OidcClient::new(
+            issuer,
+            CLIENT_ID,
+            None,
+            "http://localhost:8000/auth/oidc/callback",
+            "openid email profile",
+        )
+    }
+
+    fn login() -> LoginState {
+        LoginState {
+            state: "state".to_string(),
+            code_verifier: VERIFIER.to_string(),
+            nonce: NONCE.to_string(),
+            redirect_to: None,
+        }
+    }
+
+    #[test]
+    fn pkce_challenge_matches_rfc_7636() {
+        // Appendix B of RFC 7636
+        assert_eq!(
+            pkce_challenge(VERIFIER),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[tokio::test]
+    async fn authorization_url_uses_discovered_endpoint() {
+        let issuer = mock_issuer();
+
+        let url = client(&issuer).authorization_url(&login()).await.unwrap();
+        let url = Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.path(), "/authorize");
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["state"], "state");
+        assert_eq!(params["nonce"], NONCE);
+        assert_eq!(params["code_challenge"], pkce_challenge(VERIFIER));
+        assert_eq!(params["code_challenge_method"], "S256");
+    }
+
+    #[tokio::test]
+    async fn exchange_code_returns_verified_claims() {
+        let issuer = mock_issuer();
+
+        let claims = client(&issuer).exchange_code(CODE, &login()).await.unwrap();
+
+        assert_eq!(claims.sub, "user-1");
+        assert_eq!(claims.email.as_deref(), Some("ada@example.com"));
+        assert!(claims.email_verified);
+    }
+
+    #[tokio::test]
+    async fn exchange_code_fails_with_wrong_verifier() {
+        let issuer = mock_issuer();
+        let mut login = login();
+        login.code_verifier = "some-other-verifier".to_string();
+
+        assert!(client(&issuer).exchange_code(CODE, &login).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_id_token_rejects_tampered_tokens() {
+        let issuer = mock_issuer();
+        let client = client(&issuer);
+
+        let valid = sign(&rs256(), &claims(&issuer));
+        assert!(client.verify_id_token(&valid, NONCE).await.is_ok());
+
+        let mut wrong_audience = claims(&issuer);
+        wrong_audience["aud"] = json!("someone-else");
+        let mut wrong_issuer = claims(&issuer);
+        wrong_issuer["iss"] = json!("https://evil.example.com");
+        let mut expired = claims(&issuer);
+        expired["exp"] = json!(chrono::Utc::now().timestamp() - 3600);
+
+        for claims in [wrong_audience, wrong_issuer, expired] {
+            let token = sign(&rs256(), &claims);
+            assert!(client.verify_id_token(&token, NONCE).await.is_err());
+        }
+
+        assert!(client.verify_id_token(&valid, "other-nonce").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_id_token_rejects_symmetric_algorithms() {
+        let issuer = mock_issuer();
+
+        // Signed with the client id as an HMAC secret, as a forger might try.
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(&issuer),
+            &EncodingKey::from_secret(CLIENT_ID.as_bytes()),
+        )
+        .unwrap();
+
+        assert!(client(&issuer)
+            .verify_id_token(&token, NONCE)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    error::AppError,
+    models::{organization::Organization, user::User},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn list_organizations(pool: &PgPool) -> Result<Vec<Organization>, AppError> {
+    let organizations = sqlx::query_as::<_, Organization>(
+        "SELECT o.*, COUNT(u.id) AS member_count \
+         FROM organizations o LEFT JOIN users u ON u.organization_id = o.id \
+         GROUP BY o.id ORDER BY o.name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(organizations)
+}
+
+pub async fn create_organization(pool: &PgPool, name: &str) -> Result<Organization, AppError> {
+    let organization = sqlx::query_as::<_, Organization>(
+        "INSERT INTO organizations (id, name) VALUES ($1, $2) RETURNING *, 0::BIGINT AS member_count",
+    )
+    .bind(Uuid::new_v4())
+    .bind(name)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(organization)
+}
+
+/// Deletes an organisation. Its members stay, without an organisation.
+pub async fn delete_organization(pool: &PgPool, organization_id: &Uuid) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM organizations WHERE id = $1")
+        .bind(organization_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Organization '{}' not found",
+            organization_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Moves a user into `organization_id`, or out of any with `None`.
+pub async fn set_user_organization(
+    pool: &PgPool,
+    user_id: &Uuid,
+    organization_id: Option<&Uuid>,
+) -> Result<User, AppError> {
+    if let Some(organization_id) = organization_id {
+        let exists = sqlx::query("SELECT 1 FROM organizations WHERE id = $1")
+            .bind(organization_id)
+            .fetch_optional(pool)
+            .await?;
+
+        if exists.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Organization '{}' not found",
+                organization_id
+            )));
+        }
+    }
+
+    sqlx::query_as::<_, User>(
+        "UPDATE users SET organization_id = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(user_id)
+    .bind(organization_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("User '{}' not found", user_id)))
+}
@@ -0,0 +1,262 @@
+use crate::{config::Config, error::AppError};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A token bucket that holds `per_minute` tokens and refills at the same
+/// rate, so clients can burst up to a minute's worth of requests.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub per_minute: u32,
+}
+
+impl RateLimit {
+    fn capacity(&self) -> f64 {
+        self.per_minute as f64
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        self.per_minute as f64 / 60.0
+    }
+}
+
+/// The outcome of taking a token, with everything needed for the
+/// `RateLimit-*` and `Retry-After` headers. Times are in whole seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_after: u64,
+    pub retry_after: u64,
+}
+
+impl Decision {
+    fn new(limit: &RateLimit, tokens: f64, allowed: bool) -> Self {
+        let rate = limit.refill_per_second();
+
+        Self {
+            allowed,
+            limit: limit.per_minute,
+            remaining: tokens.max(0.0).floor() as u32,
+            reset_after: ((limit.capacity() - tokens).max(0.0) / rate).ceil() as u64,
+            retry_after: if allowed {
+                0
+            } else {
+                ((1.0 - tokens).max(0.0) / rate).ceil().max(1.0) as u64
+            },
+        }
+    }
+}
+
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Takes a token from `bucket` if one is available.
+    async fn take(&self, bucket: &str, limit: &RateLimit) -> Result<Decision, AppError>;
+
+    /// Forgets buckets that have been idle long enough to be full again.
+    async fn prune(&self) -> Result<u64, AppError>;
+}
+
+/// Picks the store named by `RATE_LIMIT_BACKEND`: `postgres`, which shares
+/// counters between replicas, or `memory` (the default).
+pub fn from_config(config: &Config, pool: &PgPool) -> Result<Arc<dyn RateLimitStore>, AppError> {
+    match config.rate_limit_backend.as_str() {
+        "postgres" => Ok(Arc::new(PostgresStore { pool: pool.clone() })),
+        "memory" => Ok(Arc::new(MemoryStore::default())),
+        other => Err(AppError::InternalServerError(format!(
+            "Unknown rate limit backend '{}'",
+            other
+        ))),
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.capacity(),
+            updated_at: now,
+        }
+    }
+
+    /// Refills the bucket for the time since it was last used, then takes a
+    /// token if there is a whole one.
+    fn take(&mut self, limit: &RateLimit, now: Instant) -> Decision {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        let tokens = (self.tokens + elapsed * limit.refill_per_second()).min(limit.capacity());
+        let allowed = tokens >= 1.0;
+
+        self.tokens = if allowed { tokens - 1.0 } else { tokens };
+        self.updated_at = now;
+
+        Decision::new(limit, self.tokens, allowed)
+    }
+}
+
+/// Keeps buckets in this process only.
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn take(&self, bucket: &str, limit: &RateLimit) -> Result<Decision, AppError> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+
+        let decision = buckets
+            .entry(bucket.to_string())
+            .or_insert_with(|| Bucket::full(limit, now))
+            .take(limit, now);
+
+        Ok(decision)
+    }
+
+    async fn prune(&self) -> Result<u64, AppError> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let before = buckets.len();
+
+        buckets.retain(|_, bucket| bucket.updated_at.elapsed() < PRUNE_INTERVAL);
+
+        Ok((before - buckets.len()) as u64)
+    }
+}
+
+/// Keeps buckets in the `rate_limit_buckets` table, updated with a single
+/// upsert per request so concurrent replicas never double-spend a token.
+pub struct PostgresStore {
+    pool: PgPool,
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresStore {
+    async fn take(&self, bucket: &str, limit: &RateLimit) -> Result<Decision, AppError> {
+        let (tokens, allowed) = sqlx::query_as::<_, (f64, bool)>(
+            "INSERT INTO rate_limit_buckets AS b (bucket, tokens, allowed, updated_at) \
+             VALUES ($1, $2 - 1, TRUE, NOW()) \
+             ON CONFLICT (bucket) DO UPDATE SET \
+                 tokens = LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3) \
+                     - (LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3) >= 1)::INT, \
+                 allowed = LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3) >= 1, \
+                 updated_at = NOW() \
+             RETURNING tokens, allowed",
+        )
+        .bind(bucket)
+        .bind(limit.capacity())
+        .bind(limit.refill_per_second())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Decision::new(limit, tokens, allowed))
+    }
+
+    async fn prune(&self) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "DELETE FROM rate_limit_buckets WHERE updated_at < NOW() - make_interval(secs => $1)",
+        )
+        .bind(PRUNE_INTERVAL.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Prunes idle buckets every few minutes. Buckets refill within a minute,
+/// so nothing is lost by dropping them.
+pub fn spawn_prune_task(store: Arc<dyn RateLimitStore>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = store.prune().await {
+                tracing::error!("Failed to prune rate limit buckets: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit { per_minute: 60 };
+
+    #[test]
+    fn bucket_allows_a_burst_of_capacity() {
+        let now = Instant::now();
+        let mut bucket = Bucket::full(&LIMIT, now);
+
+        for remaining in (0..60).rev() {
+            let decision = bucket.take(&LIMIT, now);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+
+        let decision = bucket.take(&LIMIT, now);
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.retry_after, 1);
+        assert_eq!(decision.reset_after, 60);
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 0.0,
+            updated_at: now,
+        };
+
+        assert!(!bucket.take(&LIMIT, now).allowed);
+
+        // One token a second at 60 per minute
+        let decision = bucket.take(&LIMIT, now + Duration::from_millis(2500));
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 1);
+        assert_eq!(decision.reset_after, 59);
+    }
+
+    #[test]
+    fn bucket_never_holds_more_than_capacity() {
+        let now = Instant::now();
+        let mut bucket = Bucket::full(&LIMIT, now);
+
+        let decision = bucket.take(&LIMIT, now + Duration::from_secs(3600));
+        assert_eq!(decision.remaining, 59);
+    }
+
+    #[test]
+    fn retry_after_waits_for_a_whole_token() {
+        let slow = RateLimit { per_minute: 6 };
+        let decision = Decision::new(&slow, 0.5, false);
+
+        assert_eq!(decision.retry_after, 5);
+        assert_eq!(decision.reset_after, 55);
+    }
+
+    #[tokio::test]
+    async fn memory_store_keeps_buckets_apart() {
+        let store = MemoryStore::default();
+        let limit = RateLimit { per_minute: 1 };
+
+        assert!(store.take("lookup:ip:1", &limit).await.unwrap().allowed);
+        assert!(!store.take("lookup:ip:1", &limit).await.unwrap().allowed);
+        assert!(store.take("lookup:ip:2", &limit).await.unwrap().allowed);
+        assert_eq!(store.prune().await.unwrap(), 0);
+    }
+}
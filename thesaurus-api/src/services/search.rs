@@ -1,8 +1,100 @@
 use crate::{
+    config::Config,
     error::AppError,
-    models::word::{RelationType, SearchFilters, SearchResponse, Word},
+    models::{
+        suggestion::Suggestion,
+        user::User,
+        word::{
+            RelationType, RelationTypeInfo, SearchFilters, SearchResponse, Sense, SenseGroup, Word,
+            WordRelation, WordRelations,
+        },
+    },
+    services::{
+        autocomplete, spellcheck::word_not_found, suggestions::visible_suggestions,
+        word_cache::WordCache,
+    },
+};
+use meilisearch_sdk::{
+    client::Client as MeiliClient,
+    search::{SearchQuery, SearchResults, Selectors},
 };
-use meilisearch_sdk::{client::Client as MeiliClient, search::SearchResults};
+use once_cell::sync::OnceCell;
+use sqlx::PgPool;
+use std::{collections::BTreeMap, num::NonZeroUsize, time::Duration};
+
+const INDEX_VERSION_INTERVAL: Duration = Duration::from_secs(30);
+
+static WORD_CACHE: OnceCell<WordCache> = OnceCell::new();
+
+/// Turns on the word cache. A `WORD_CACHE_SIZE` of zero leaves it off.
+pub fn init_word_cache(config: &Config) {
+    let Some(capacity) = NonZeroUsize::new(config.word_cache_size) else {
+        tracing::info!("Word cache disabled");
+        return;
+    };
+
+    let ttl = Duration::from_secs(config.word_cache_ttl_secs);
+
+    if WORD_CACHE.set(WordCache::new(capacity, ttl)).is_ok() {
+        tracing::info!("Word cache enabled for {} entries", capacity);
+    }
+}
+
+pub fn word_cache() -> Option<&'static WordCache> {
+    WORD_CACHE.get()
+}
+
+/// Polls the `words` index and, whenever it has been updated (for example
+/// by a re-import), clears the word cache and rebuilds autocomplete. The
+/// first poll builds autocomplete at startup.
+pub fn spawn_index_version_task(client: MeiliClient) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INDEX_VERSION_INTERVAL);
+        let mut autocomplete_version = None;
+
+        loop {
+            interval.tick().await;
+
+            let version = match client.get_index("words").await {
+                Ok(index) => index.updated_at.map(|updated_at| updated_at.to_string()),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch the words index version: {}", e);
+                    continue;
+                }
+            };
+
+            if let (Some(cache), Some(version)) = (word_cache(), &version) {
+                if cache.set_index_version(version.clone()) {
+                    tracing::info!("Words index updated, cleared the word cache");
+                }
+            }
+
+            if autocomplete_version.as_ref() != Some(&version) {
+                match autocomplete::rebuild(&client).await {
+                    Ok(count) => {
+                        tracing::info!("Built autocomplete from {} words", count);
+                        autocomplete_version = Some(version);
+                    }
+                    Err(e) => tracing::error!("Failed to build autocomplete: {}", e),
+                }
+            }
+        }
+    });
+}
+
+/// Quotes `value` for a Meilisearch filter expression, so user input can
+/// not end the string early or add conditions of its own.
+pub fn quote_filter_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn word_filter(word: &str, lang: &str) -> String {
+    format!(
+        "word = {} AND language = {}",
+        quote_filter_value(word),
+        quote_filter_value(lang)
+    )
+}
 
 pub async fn search_words(
     client: &MeiliClient,
@@ -13,31 +105,31 @@ pub async fn search_words(
 ) -> Result<SearchResponse, AppError> {
     let index = client.index("words");
 
-    let mut pos_filter = None;
-    let mut exact_match_filter = None;
+    let mut conditions = Vec::new();
 
     if let Some(filters) = filters {
+        conditions.push(format!("language = {}", quote_filter_value(&filters.lang)));
+
         if let Some(pos) = filters.pos {
-            pos_filter = Some(format!("pos = \"{}\"", pos));
+            conditions.push(format!("pos = {}", quote_filter_value(&pos)));
         }
 
         if let Some(true) = filters.exact_match {
-            exact_match_filter = Some(format!("word = \"{}\"", query));
+            conditions.push(format!("word = {}", quote_filter_value(query)));
         }
     }
 
+    // `with_filter` replaces any previous filter, so combine them up front.
+    let filter_str = conditions.join(" AND ");
+
     let mut search_query = index.search();
     search_query
         .with_query(query)
         .with_offset(offset)
         .with_limit(limit);
 
-    if let Some(filter) = &pos_filter {
-        search_query.with_filter(filter);
-    }
-
-    if let Some(filter) = &exact_match_filter {
-        search_query.with_filter(filter);
+    if !filter_str.is_empty() {
+        search_query.with_filter(&filter_str);
     }
 
     let search_results: SearchResults<Word> = search_query.execute().await?;
@@ -56,13 +148,36 @@ pub async fn search_words(
     })
 }
 
+/// Looks up `word` through the word cache when it is enabled.
 pub async fn get_word_by_exact_match(
     client: &MeiliClient,
     word: &str,
+    lang: &str,
+) -> Result<Option<Word>, AppError> {
+    let Some(cache) = word_cache() else {
+        return fetch_word_by_exact_match(client, word, lang).await;
+    };
+
+    if let Some(cached) = cache.get(word, lang) {
+        return Ok(cached);
+    }
+
+    let generation = cache.generation();
+    let result = fetch_word_by_exact_match(client, word, lang).await?;
+    cache.insert(word, lang, result.clone(), generation);
+
+    Ok(result)
+}
+
+/// Looks up `word` in Meilisearch, skipping the word cache.
+pub async fn fetch_word_by_exact_match(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
 ) -> Result<Option<Word>, AppError> {
     let index = client.index("words");
 
-    let filter_str = format!("word = \"{}\"", word);
+    let filter_str = word_filter(word, lang);
 
     let mut search_query = index.search();
     search_query
@@ -79,30 +194,267 @@ pub async fn get_word_by_exact_match(
         .map(|result| result.result))
 }
 
+/// Looks up every word in `words`, going to Meilisearch once with a
+/// multi-search for the ones the word cache does not have. Each query
+/// matches what [`fetch_word_by_exact_match`] sends on its own.
+pub async fn get_words_by_exact_match(
+    client: &MeiliClient,
+    words: &[String],
+    lang: &str,
+) -> Result<BTreeMap<String, Option<Word>>, AppError> {
+    let cache = word_cache();
+    let mut found = BTreeMap::new();
+    let mut missing = Vec::new();
+
+    for word in words {
+        if found.contains_key(word) || missing.contains(word) {
+            continue;
+        }
+
+        match cache.and_then(|cache| cache.get(word, lang)) {
+            Some(cached) => {
+                found.insert(word.clone(), cached);
+            }
+            None => missing.push(word.clone()),
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(found);
+    }
+
+    let generation = cache.map(|cache| cache.generation());
+    let results = fetch_words_by_exact_match(client, &missing, lang).await?;
+
+    for (word, result) in missing.into_iter().zip(results) {
+        if let (Some(cache), Some(generation)) = (cache, generation) {
+            cache.insert(&word, lang, result.clone(), generation);
+        }
+
+        found.insert(word, result);
+    }
+
+    Ok(found)
+}
+
+/// Looks up every word in `words` with one multi-search, skipping the word
+/// cache. Results come back in the order of `words`.
+pub async fn fetch_words_by_exact_match(
+    client: &MeiliClient,
+    words: &[String],
+    lang: &str,
+) -> Result<Vec<Option<Word>>, AppError> {
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let index = client.index("words");
+    let filters: Vec<String> = words.iter().map(|word| word_filter(word, lang)).collect();
+
+    let mut multi_search = client.multi_search();
+    for (word, filter_str) in words.iter().zip(&filters) {
+        multi_search.with_search_query(
+            SearchQuery::new(&index)
+                .with_query(word)
+                .with_filter(filter_str)
+                .with_limit(1)
+                .build(),
+        );
+    }
+
+    let response = multi_search.execute::<Word>().await?;
+
+    Ok(response
+        .results
+        .into_iter()
+        .map(|results| results.hits.into_iter().next().map(|hit| hit.result))
+        .collect())
+}
+
+/// Empties the relation lists of `word` that are not in `keep`, along with
+/// the typed relations folded into them.
+pub fn retain_relations(word: &mut Word, keep: &[RelationType]) {
+    for relation_type in RelationType::ALL {
+        if !keep.contains(&relation_type) {
+            relation_list_mut(word, relation_type).clear();
+        }
+    }
+
+    word.relations.retain(|relation| {
+        relation
+            .relation_type()
+            .is_some_and(|relation_type| keep.contains(&relation_type))
+    });
+}
+
+/// Words related to `word` by `relation_type`, with the viewer's visible
+/// suggestions appended. The suggestions that were merged in are returned
+/// alongside so callers can tell them apart from WordNet data.
 pub async fn get_relations(
     client: &MeiliClient,
+    pool: &PgPool,
+    viewer: Option<&User>,
     word: &str,
+    lang: &str,
     relation_type: RelationType,
-) -> Result<Vec<String>, AppError> {
-    let word_result = get_word_by_exact_match(client, word).await?;
+) -> Result<(Vec<String>, Vec<Suggestion>), AppError> {
+    let word_result = get_word_by_exact_match(client, word, lang).await?;
 
-    match word_result {
-        Some(word_obj) => match relation_type {
-            RelationType::Synonym => Ok(word_obj.synonyms),
-            RelationType::Antonym => Ok(word_obj.antonyms),
-            RelationType::BroaderTerm => Ok(word_obj.broader_terms),
-            RelationType::NarrowerTerm => Ok(word_obj.narrower_terms),
-            RelationType::RelatedTerm => Ok(word_obj.related_terms),
-        },
-        None => Ok(Vec::new()),
+    let mut related = match word_result {
+        Some(mut word_obj) => std::mem::take(relation_list_mut(&mut word_obj, relation_type)),
+        None => Vec::new(),
+    };
+
+    let suggestions = match viewer {
+        Some(viewer) => visible_suggestions(pool, viewer, word, lang).await?,
+        None => Vec::new(),
+    };
+
+    let merged = merge_suggestions(&mut related, suggestions, relation_type);
+
+    Ok((related, merged))
+}
+
+pub fn relation_list_mut(word: &mut Word, relation_type: RelationType) -> &mut Vec<String> {
+    match relation_type {
+        RelationType::Synonym => &mut word.synonyms,
+        RelationType::Antonym => &mut word.antonyms,
+        RelationType::BroaderTerm => &mut word.broader_terms,
+        RelationType::NarrowerTerm => &mut word.narrower_terms,
+        RelationType::RelatedTerm => &mut word.related_terms,
     }
 }
 
+/// Appends the suggestions for `relation_type` that `related` does not
+/// already contain and returns them.
+fn merge_suggestions(
+    related: &mut Vec<String>,
+    suggestions: Vec<Suggestion>,
+    relation_type: RelationType,
+) -> Vec<Suggestion> {
+    let mut merged = Vec::new();
+
+    for suggestion in suggestions {
+        if suggestion.relation != relation_type.as_str()
+            || related
+                .iter()
+                .any(|w| w.eq_ignore_ascii_case(&suggestion.related_word))
+        {
+            continue;
+        }
+
+        related.push(suggestion.related_word.clone());
+        merged.push(suggestion);
+    }
+
+    merged
+}
+
+/// Relations of `word` with their original WordNet `relType`, optionally
+/// narrowed down to a single type such as `mero_part`.
+pub async fn get_typed_relations(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
+    rel_type: Option<&str>,
+) -> Result<Vec<WordRelation>, AppError> {
+    let word_obj = get_word_by_exact_match(client, word, lang)
+        .await?
+        .ok_or_else(|| word_not_found(word, lang))?;
+
+    Ok(word_obj
+        .relations
+        .into_iter()
+        .filter(|relation| rel_type.is_none_or(|t| relation.rel_type == t))
+        .collect())
+}
+
+/// Every relation type present in the index for `lang`, with the number of
+/// words that have at least one relation of that type. The coarse list each
+/// type is folded into is read back from a word that has it, as written by
+/// the importer.
+pub async fn get_relation_types(
+    client: &MeiliClient,
+    lang: &str,
+) -> Result<Vec<RelationTypeInfo>, AppError> {
+    let index = client.index("words");
+
+    let filter_str = format!("language = {}", quote_filter_value(lang));
+
+    let mut search_query = index.search();
+    search_query
+        .with_filter(&filter_str)
+        .with_facets(Selectors::Some(&["relations.rel_type"]))
+        .with_limit(0);
+
+    let search_results: SearchResults<Word> = search_query.execute().await?;
+
+    let counts: Vec<(String, usize)> = search_results
+        .facet_distribution
+        .and_then(|mut facets| facets.remove("relations.rel_type"))
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    if counts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let filters: Vec<String> = counts
+        .iter()
+        .map(|(rel_type, _)| {
+            format!(
+                "{} AND relations.rel_type = {}",
+                filter_str,
+                quote_filter_value(rel_type)
+            )
+        })
+        .collect();
+
+    let mut multi_search = client.multi_search();
+    for filter in &filters {
+        multi_search.with_search_query(
+            SearchQuery::new(&index)
+                .with_filter(filter)
+                .with_attributes_to_retrieve(Selectors::Some(&["relations"]))
+                .with_limit(1)
+                .build(),
+        );
+    }
+
+    let response = multi_search.execute::<WordRelations>().await?;
+
+    let mut types: Vec<RelationTypeInfo> = counts
+        .into_iter()
+        .zip(response.results)
+        .map(|((rel_type, count), results)| {
+            let coarse = results
+                .hits
+                .into_iter()
+                .flat_map(|hit| hit.result.relations)
+                .find(|relation| relation.rel_type == rel_type)
+                .map(|relation| relation.bucket)
+                .unwrap_or_default();
+
+            RelationTypeInfo {
+                rel_type,
+                coarse,
+                count,
+            }
+        })
+        .collect();
+
+    types.sort_by(|a, b| a.rel_type.cmp(&b.rel_type));
+
+    Ok(types)
+}
+
 pub async fn get_word_definition(
     client: &MeiliClient,
     word: &str,
+    lang: &str,
 ) -> Result<Vec<String>, AppError> {
-    let word_result = get_word_by_exact_match(client, word).await?;
+    let word_result = get_word_by_exact_match(client, word, lang).await?;
 
     match word_result {
         Some(word_obj) => Ok(word_obj.definitions),
@@ -110,8 +462,12 @@ pub async fn get_word_definition(
     }
 }
 
-pub async fn get_word_examples(client: &MeiliClient, word: &str) -> Result<Vec<String>, AppError> {
-    let word_result = get_word_by_exact_match(client, word).await?;
+pub async fn get_word_examples(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
+) -> Result<Vec<String>, AppError> {
+    let word_result = get_word_by_exact_match(client, word, lang).await?;
 
     match word_result {
         Some(word_obj) => Ok(word_obj.examples),
@@ -119,13 +475,98 @@ pub async fn get_word_examples(client: &MeiliClient, word: &str) -> Result<Vec<S
     }
 }
 
-pub async fn get_all_word_relations(client: &MeiliClient, word: &str) -> Result<Word, AppError> {
-    let word_result = get_word_by_exact_match(client, word).await?;
+/// The full word entry with the viewer's visible suggestions merged into
+/// its relation lists and listed under `suggestions`.
+pub async fn get_all_word_relations(
+    client: &MeiliClient,
+    pool: &PgPool,
+    viewer: Option<&User>,
+    word: &str,
+    lang: &str,
+) -> Result<Word, AppError> {
+    let mut word_obj = get_word_by_exact_match(client, word, lang)
+        .await?
+        .ok_or_else(|| word_not_found(word, lang))?;
+
+    let Some(viewer) = viewer else {
+        return Ok(word_obj);
+    };
+
+    let mut suggestions = visible_suggestions(pool, viewer, word, lang).await?;
+
+    for relation_type in RelationType::ALL {
+        let (matching, rest) = suggestions
+            .into_iter()
+            .partition(|s| s.relation == relation_type.as_str());
+        suggestions = rest;
+
+        let merged = merge_suggestions(
+            relation_list_mut(&mut word_obj, relation_type),
+            matching,
+            relation_type,
+        );
+        word_obj.suggestions.extend(merged);
+    }
 
-    match word_result {
-        Some(word_obj) => Ok(word_obj),
-        None => Err(AppError::NotFound(format!("Word '{}' not found", word))),
+    Ok(word_obj)
+}
+
+/// Part-of-speech order used when grouping senses: nouns, verbs, adjectives
+/// (including satellites), adverbs, then anything else.
+const POS_ORDER: [&str; 5] = ["n", "v", "a", "s", "r"];
+
+const MAX_SENSES: usize = 1000;
+
+pub async fn get_word_senses(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
+) -> Result<Vec<SenseGroup>, AppError> {
+    let index = client.index("senses");
+
+    let filter_str = word_filter(word, lang);
+
+    let mut search_query = index.search();
+    search_query.with_filter(&filter_str).with_limit(MAX_SENSES);
+
+    let search_results: SearchResults<Sense> = search_query.execute().await?;
+
+    let mut senses: Vec<Sense> = search_results
+        .hits
+        .into_iter()
+        .map(|result| result.result)
+        .collect();
+
+    if senses.is_empty() {
+        return Err(word_not_found(word, lang));
+    }
+
+    senses.sort_by(|a, b| {
+        pos_rank(&a.pos)
+            .cmp(&pos_rank(&b.pos))
+            .then_with(|| a.pos.cmp(&b.pos))
+            .then_with(|| a.sense_number.cmp(&b.sense_number))
+    });
+
+    let mut groups: Vec<SenseGroup> = Vec::new();
+    for sense in senses {
+        match groups.last_mut() {
+            Some(group) if group.pos == sense.pos => group.senses.push(sense),
+            _ => groups.push(SenseGroup {
+                pos: sense.pos.clone(),
+                senses: vec![sense],
+            }),
+        }
     }
+
+    Ok(groups)
+}
+
+fn pos_rank(pos: &str) -> usize {
+    POS_ORDER
+        .iter()
+        .position(|p| *p == pos)
+        .unwrap_or(POS_ORDER.len())
 }
 
 pub async fn setup_meilisearch(client: &MeiliClient) -> Result<(), AppError> {
@@ -141,7 +582,13 @@ pub async fn setup_meilisearch(client: &MeiliClient) -> Result<(), AppError> {
             .await?;
 
         words_index
-            .set_filterable_attributes(&["word", "pos"])
+            .set_filterable_attributes(&[
+                "word",
+                "pos",
+                "language",
+                "lexicon",
+                "relations.rel_type",
+            ])
             .await?;
 
         words_index.set_sortable_attributes(&["word"]).await?;
@@ -162,5 +609,93 @@ pub async fn setup_meilisearch(client: &MeiliClient) -> Result<(), AppError> {
         tracing::info!("Meilisearch index already exists");
     }
 
+    let senses_index = client.index("senses");
+
+    if senses_index.get_stats().await.is_err() {
+        tracing::info!("Creating senses index in Meilisearch");
+
+        senses_index
+            .set_searchable_attributes(&["word", "definition", "synonyms", "examples"])
+            .await?;
+
+        senses_index
+            .set_filterable_attributes(&[
+                "word",
+                "word_id",
+                "pos",
+                "synset_id",
+                "language",
+                "lexicon",
+            ])
+            .await?;
+
+        senses_index
+            .set_sortable_attributes(&["word", "sense_number"])
+            .await?;
+
+        tracing::info!("Meilisearch senses index created and configured");
+    } else {
+        tracing::info!("Meilisearch senses index already exists");
+    }
+
+    let synsets_index = client.index("synsets");
+
+    if synsets_index.get_stats().await.is_err() {
+        tracing::info!("Creating synsets index in Meilisearch");
+
+        synsets_index
+            .set_searchable_attributes(&["lemmas", "definition"])
+            .await?;
+
+        synsets_index
+            .set_filterable_attributes(&["synset_id", "ili", "pos", "language", "lexicon"])
+            .await?;
+
+        tracing::info!("Meilisearch synsets index created and configured");
+    } else {
+        tracing::info!("Meilisearch synsets index already exists");
+    }
+
+    let concepts_index = client.index("concepts");
+
+    if concepts_index.get_stats().await.is_err() {
+        tracing::info!("Creating concepts index in Meilisearch");
+
+        concepts_index
+            .set_searchable_attributes(&["synsets.lemmas", "synsets.definition"])
+            .await?;
+
+        concepts_index
+            .set_filterable_attributes(&["ili", "languages"])
+            .await?;
+
+        tracing::info!("Meilisearch concepts index created and configured");
+    } else {
+        tracing::info!("Meilisearch concepts index already exists");
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_filter_value_escapes_quotes_and_backslashes() {
+        assert_eq!(quote_filter_value("en"), "\"en\"");
+        assert_eq!(
+            quote_filter_value("en\" OR language = \"fr"),
+            "\"en\\\" OR language = \\\"fr\""
+        );
+        assert_eq!(quote_filter_value("a\\\"b"), "\"a\\\\\\\"b\"");
+    }
+
+    #[test]
+    fn word_filter_quotes_both_values() {
+        assert_eq!(
+            word_filter("rock \"n\" roll", "en"),
+            "word = \"rock \\\"n\\\" roll\" AND language = \"en\""
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    config::Config,
+    error::AppError,
+    models::session::Session,
+    services::tokens::{generate_opaque_token, hash_token},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn insert_session<'e, E>(
+    executor: E,
+    config: &Config,
+    user_id: &Uuid,
+    family_id: &Uuid,
+) -> Result<(Session, String), AppError>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    let refresh_token = generate_opaque_token();
+
+    let session = sqlx::query_as::<_, Session>(
+        "INSERT INTO user_sessions (id, user_id, family_id, token, expires_at) \
+         VALUES ($1, $2, $3, $4, NOW() + make_interval(days => $5)) \
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(family_id)
+    .bind(hash_token(&refresh_token))
+    .bind(config.refresh_token_days as i32)
+    .fetch_one(executor)
+    .await?;
+
+    Ok((session, refresh_token))
+}
+
+/// Starts a new session and returns it with its refresh token.
+pub async fn create_session(
+    pool: &PgPool,
+    config: &Config,
+    user_id: &Uuid,
+) -> Result<(Session, String), AppError> {
+    sqlx::query("DELETE FROM user_sessions WHERE user_id = $1 AND expires_at < NOW()")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    insert_session(pool, config, user_id, &Uuid::new_v4()).await
+}
+
+/// Exchanges a refresh token for a new one in the same session. Presenting
+/// a token that was already rotated means it leaked, so the whole session
+/// is revoked.
+pub async fn rotate_session(
+    pool: &PgPool,
+    config: &Config,
+    refresh_token: &str,
+) -> Result<(Session, String), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let session =
+        sqlx::query_as::<_, Session>("SELECT * FROM user_sessions WHERE token = $1 FOR UPDATE")
+            .bind(hash_token(refresh_token))
+            .fetch_optional(&mut tx)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+    if session.revoked_at.is_some() || session.expires_at < chrono::Utc::now() {
+        return Err(AppError::Unauthorized);
+    }
+
+    if session.rotated_at.is_some() {
+        sqlx::query(
+            "UPDATE user_sessions SET revoked_at = NOW() \
+             WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(session.family_id)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::warn!(
+            "Refresh token reuse detected for user {}, revoked session {}",
+            session.user_id,
+            session.family_id
+        );
+
+        return Err(AppError::Unauthorized);
+    }
+
+    sqlx::query("UPDATE user_sessions SET rotated_at = NOW() WHERE id = $1")
+        .bind(session.id)
+        .execute(&mut tx)
+        .await?;
+
+    let rotated = insert_session(&mut tx, config, &session.user_id, &session.family_id).await?;
+
+    tx.commit().await?;
+
+    Ok(rotated)
+}
+
+pub async fn revoke_session(
+    pool: &PgPool,
+    user_id: &Uuid,
+    family_id: &Uuid,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE user_sessions SET revoked_at = NOW() \
+         WHERE user_id = $1 AND family_id = $2 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Revokes every session of the user and returns how many were active.
+pub async fn revoke_all_sessions(pool: &PgPool, user_id: &Uuid) -> Result<i64, AppError> {
+    let revoked: i64 = sqlx::query_scalar(
+        "WITH revoked AS ( \
+             UPDATE user_sessions SET revoked_at = NOW() \
+             WHERE user_id = $1 AND revoked_at IS NULL \
+             RETURNING family_id) \
+         SELECT COUNT(DISTINCT family_id) FROM revoked",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(revoked)
+}
+
+/// Whether access tokens issued for the session may still be used.
+pub async fn is_session_active(
+    pool: &PgPool,
+    user_id: &Uuid,
+    family_id: &Uuid,
+) -> Result<bool, AppError> {
+    let active: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM user_sessions \
+         WHERE user_id = $1 AND family_id = $2 AND revoked_at IS NULL AND expires_at > NOW())",
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(active)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_user, test_config, test_pool};
+
+    #[tokio::test]
+    async fn rotation_issues_a_new_token_in_the_same_family() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let config = test_config();
+        let user = create_user(&pool).await;
+
+        let (session, token) = create_session(&pool, &config, &user.id).await.unwrap();
+        let (rotated, rotated_token) = rotate_session(&pool, &config, &token).await.unwrap();
+
+        assert_eq!(rotated.family_id, session.family_id);
+        assert_ne!(rotated.id, session.id);
+        assert_ne!(rotated_token, token);
+        assert!(is_session_active(&pool, &user.id, &session.family_id)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn replaying_a_rotated_token_revokes_the_family() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let config = test_config();
+        let user = create_user(&pool).await;
+
+        let (session, token) = create_session(&pool, &config, &user.id).await.unwrap();
+        let (other, _) = create_session(&pool, &config, &user.id).await.unwrap();
+        let (_, rotated_token) = rotate_session(&pool, &config, &token).await.unwrap();
+
+        assert!(matches!(
+            rotate_session(&pool, &config, &token).await,
+            Err(AppError::Unauthorized)
+        ));
+
+        let unrevoked: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_sessions WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(session.family_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(unrevoked, 0);
+        assert!(!is_session_active(&pool, &user.id, &session.family_id)
+            .await
+            .unwrap());
+        assert!(matches!(
+            rotate_session(&pool, &config, &rotated_token).await,
+            Err(AppError::Unauthorized)
+        ));
+
+        // Other sessions of the user are left alone.
+        assert!(is_session_active(&pool, &user.id, &other.family_id)
+            .await
+            .unwrap());
+    }
+}
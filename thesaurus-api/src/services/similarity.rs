@@ -0,0 +1,408 @@
+use crate::{
+    error::AppError,
+    models::word::{SimilarityPair, SimilarityResponse, SimilaritySense, Synset},
+    services::{
+        graph::{get_hypernym_closure, matches_relation},
+        search::get_word_senses,
+    },
+};
+use meilisearch_sdk::client::Client as MeiliClient;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Deepest hypernym chains per part of speech, as used by NLTK for
+/// Leacock-Chodorow (verbs include the simulated root).
+const TAXONOMY_DEPTHS: [(&str, usize); 2] = [("n", 19), ("v", 13)];
+
+/// Computes path, Wu-Palmer and Leacock-Chodorow similarity for every pair
+/// of senses of `a` and `b` that share a part of speech with a hypernym
+/// taxonomy (nouns and verbs).
+pub async fn compare_words(
+    client: &MeiliClient,
+    a: &str,
+    b: &str,
+    lang: &str,
+) -> Result<SimilarityResponse, AppError> {
+    let senses_a = sense_synsets(client, a, lang).await?;
+    let senses_b = sense_synsets(client, b, lang).await?;
+
+    let start_ids: Vec<String> = senses_a
+        .iter()
+        .chain(senses_b.iter())
+        .map(|(id, _)| id.clone())
+        .collect();
+    let synsets = get_hypernym_closure(client, &start_ids).await?;
+
+    let mut max_depths = HashMap::new();
+    let mut pairs = Vec::new();
+
+    for (id_a, pos_a) in &senses_a {
+        for (id_b, pos_b) in &senses_b {
+            if pos_a != pos_b {
+                continue;
+            }
+
+            let taxonomy_depth = match TAXONOMY_DEPTHS.iter().find(|(pos, _)| pos == pos_a) {
+                Some((_, depth)) => *depth,
+                None => continue,
+            };
+
+            let (synset_a, synset_b) = match (synsets.get(id_a), synsets.get(id_b)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => continue,
+            };
+
+            let scores = match score_pair(
+                synset_a,
+                synset_b,
+                &synsets,
+                &mut max_depths,
+                taxonomy_depth,
+            ) {
+                Some(scores) => scores,
+                None => continue,
+            };
+
+            pairs.push(SimilarityPair {
+                a: similarity_sense(synset_a),
+                b: similarity_sense(synset_b),
+                lowest_common_hypernym: scores.lowest_common_hypernym,
+                path: scores.path,
+                wu_palmer: scores.wu_palmer,
+                leacock_chodorow: scores.leacock_chodorow,
+            });
+        }
+    }
+
+    pairs.sort_by(|x, y| {
+        y.path
+            .total_cmp(&x.path)
+            .then_with(|| y.wu_palmer.total_cmp(&x.wu_palmer))
+    });
+
+    Ok(SimilarityResponse {
+        a: a.to_string(),
+        b: b.to_string(),
+        best: pairs.first().cloned(),
+        pairs,
+    })
+}
+
+/// Synset id and part of speech of every sense of `word`, in sense order.
+async fn sense_synsets(
+    client: &MeiliClient,
+    word: &str,
+    lang: &str,
+) -> Result<Vec<(String, String)>, AppError> {
+    let mut seen = HashSet::new();
+
+    Ok(get_word_senses(client, word, lang)
+        .await?
+        .into_iter()
+        .flat_map(|group| group.senses)
+        .filter(|sense| seen.insert(sense.synset_id.clone()))
+        // Adjective satellites share the adjective taxonomy.
+        .map(|sense| {
+            let pos = if sense.pos == "s" {
+                "a".to_string()
+            } else {
+                sense.pos
+            };
+            (sense.synset_id, pos)
+        })
+        .collect())
+}
+
+fn similarity_sense(synset: &Synset) -> SimilaritySense {
+    SimilaritySense {
+        synset_id: synset.synset_id.clone(),
+        pos: synset.pos.clone(),
+        definition: synset.definition.clone(),
+        lemmas: synset.lemmas.clone(),
+    }
+}
+
+struct PairScores {
+    lowest_common_hypernym: Option<String>,
+    path: f64,
+    wu_palmer: f64,
+    leacock_chodorow: f64,
+}
+
+fn score_pair(
+    a: &Synset,
+    b: &Synset,
+    synsets: &HashMap<String, Synset>,
+    max_depths: &mut HashMap<String, usize>,
+    taxonomy_depth: usize,
+) -> Option<PairScores> {
+    let ancestors_a = hypernym_distances(&a.synset_id, synsets);
+    let ancestors_b = hypernym_distances(&b.synset_id, synsets);
+
+    // Shortest path through any common hypernym, and the deepest common
+    // hypernym for Wu-Palmer.
+    let mut shortest: Option<usize> = None;
+    let mut lowest: Option<(&str, usize, usize)> = None;
+
+    for (ancestor, dist_a) in &ancestors_a {
+        let dist_b = match ancestors_b.get(ancestor) {
+            Some(d) => *d,
+            None => continue,
+        };
+
+        let distance = dist_a + dist_b;
+        if shortest.is_none_or(|s| distance < s) {
+            shortest = Some(distance);
+        }
+
+        let depth = max_depth(ancestor, synsets, max_depths) + 1;
+        let replace = match lowest {
+            None => true,
+            Some((id, best_depth, _)) => {
+                depth > best_depth || (depth == best_depth && ancestor.as_str() < id)
+            }
+        };
+        if replace {
+            lowest = Some((ancestor.as_str(), depth, distance));
+        }
+    }
+
+    let (path_length, lowest_common_hypernym, lcs_depth, lcs_distance) = match (shortest, lowest) {
+        (Some(shortest), Some((id, depth, distance))) => {
+            (shortest, Some(id.to_string()), depth, distance)
+        }
+        // Verbs have many roots; like NLTK, join them under a simulated root.
+        _ if a.pos == "v" => {
+            let distance =
+                root_distance(&ancestors_a, synsets) + root_distance(&ancestors_b, synsets) + 2;
+            (distance, None, 1, distance)
+        }
+        _ => return None,
+    };
+
+    let path = 1.0 / (path_length as f64 + 1.0);
+    let wu_palmer = (2.0 * lcs_depth as f64) / (lcs_distance as f64 + 2.0 * lcs_depth as f64);
+    let leacock_chodorow = -((path_length as f64 + 1.0) / (2.0 * taxonomy_depth as f64)).ln();
+
+    Some(PairScores {
+        lowest_common_hypernym,
+        path,
+        wu_palmer,
+        leacock_chodorow,
+    })
+}
+
+/// Shortest hypernym distance from `start` to itself and each ancestor.
+fn hypernym_distances(start: &str, synsets: &HashMap<String, Synset>) -> HashMap<String, usize> {
+    let mut distances = HashMap::from([(start.to_string(), 0)]);
+    let mut queue = VecDeque::from([start.to_string()]);
+
+    while let Some(id) = queue.pop_front() {
+        let distance = distances[&id];
+        let synset = match synsets.get(&id) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        for edge in &synset.relations {
+            if matches_relation(Some("hypernym"), &edge.rel_type)
+                && !distances.contains_key(&edge.target)
+            {
+                distances.insert(edge.target.clone(), distance + 1);
+                queue.push_back(edge.target.clone());
+            }
+        }
+    }
+
+    distances
+}
+
+/// Length of the longest hypernym chain from `id` up to a root.
+fn max_depth(
+    id: &str,
+    synsets: &HashMap<String, Synset>,
+    memo: &mut HashMap<String, usize>,
+) -> usize {
+    if let Some(depth) = memo.get(id) {
+        return *depth;
+    }
+
+    // Guards against cycles in malformed data while this node is on the stack.
+    memo.insert(id.to_string(), 0);
+
+    let depth = synsets
+        .get(id)
+        .map(|synset| {
+            synset
+                .relations
+                .iter()
+                .filter(|edge| matches_relation(Some("hypernym"), &edge.rel_type))
+                .map(|edge| max_depth(&edge.target, synsets, memo) + 1)
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0);
+
+    memo.insert(id.to_string(), depth);
+    depth
+}
+
+/// Shortest distance from the start synset to one of its roots.
+fn root_distance(ancestors: &HashMap<String, usize>, synsets: &HashMap<String, Synset>) -> usize {
+    ancestors
+        .iter()
+        .filter(|(id, _)| {
+            synsets.get(*id).is_none_or(|synset| {
+                !synset
+                    .relations
+                    .iter()
+                    .any(|edge| matches_relation(Some("hypernym"), &edge.rel_type))
+            })
+        })
+        .map(|(_, distance)| *distance)
+        .min()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::word::SynsetRelation;
+
+    fn synset(id: &str, pos: &str, hypernyms: &[&str]) -> Synset {
+        Synset {
+            id: id.to_string(),
+            synset_id: id.to_string(),
+            language: "en".to_string(),
+            lexicon: "oewn".to_string(),
+            ili: None,
+            pos: pos.to_string(),
+            definition: None,
+            lemmas: vec![id.to_string()],
+            relations: hypernyms
+                .iter()
+                .map(|target| SynsetRelation {
+                    rel_type: "hypernym".to_string(),
+                    target: target.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    //        entity
+    //       /      \
+    //   animal    artifact
+    //   /    \        \
+    // dog    cat      car
+    fn taxonomy() -> HashMap<String, Synset> {
+        [
+            synset("entity", "n", &[]),
+            synset("animal", "n", &["entity"]),
+            synset("artifact", "n", &["entity"]),
+            synset("dog", "n", &["animal"]),
+            synset("cat", "n", &["animal"]),
+            synset("car", "n", &["artifact"]),
+            synset("walk", "v", &[]),
+            synset("stroll", "v", &["walk"]),
+            synset("think", "v", &[]),
+            synset("orphan", "n", &[]),
+        ]
+        .into_iter()
+        .map(|synset| (synset.synset_id.clone(), synset))
+        .collect()
+    }
+
+    fn score(a: &str, b: &str) -> Option<PairScores> {
+        let synsets = taxonomy();
+        let depth = if synsets[a].pos == "v" { 13 } else { 19 };
+        score_pair(
+            &synsets[a],
+            &synsets[b],
+            &synsets,
+            &mut HashMap::new(),
+            depth,
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn identical_synsets_score_one() {
+        let scores = score("dog", "dog").unwrap();
+
+        assert_eq!(scores.lowest_common_hypernym.as_deref(), Some("dog"));
+        assert_close(scores.path, 1.0);
+        assert_close(scores.wu_palmer, 1.0);
+        assert_close(scores.leacock_chodorow, -(1.0f64 / 38.0).ln());
+    }
+
+    #[test]
+    fn siblings_score_through_their_parent() {
+        let scores = score("dog", "cat").unwrap();
+
+        assert_eq!(scores.lowest_common_hypernym.as_deref(), Some("animal"));
+        assert_close(scores.path, 1.0 / 3.0);
+        // animal sits at depth 2, two edges between dog and cat
+        assert_close(scores.wu_palmer, 4.0 / 6.0);
+        assert_close(scores.leacock_chodorow, -(3.0f64 / 38.0).ln());
+    }
+
+    #[test]
+    fn cousins_score_through_the_root() {
+        let scores = score("dog", "car").unwrap();
+
+        assert_eq!(scores.lowest_common_hypernym.as_deref(), Some("entity"));
+        assert_close(scores.path, 1.0 / 5.0);
+        assert_close(scores.wu_palmer, 2.0 / 6.0);
+        assert!(scores.path < score("dog", "cat").unwrap().path);
+    }
+
+    #[test]
+    fn unrelated_verbs_meet_at_a_simulated_root() {
+        let scores = score("stroll", "think").unwrap();
+
+        assert_eq!(scores.lowest_common_hypernym, None);
+        // stroll -> walk -> (root) <- think
+        assert_close(scores.path, 1.0 / 4.0);
+        assert_close(scores.wu_palmer, 2.0 / 5.0);
+    }
+
+    #[test]
+    fn unrelated_nouns_have_no_score() {
+        assert!(score("dog", "orphan").is_none());
+    }
+
+    #[test]
+    fn hypernym_distances_follow_instance_hypernyms() {
+        let mut synsets = taxonomy();
+        let mut rex = synset("rex", "n", &[]);
+        rex.relations.push(SynsetRelation {
+            rel_type: "instance_hypernym".to_string(),
+            target: "dog".to_string(),
+        });
+        synsets.insert("rex".to_string(), rex);
+
+        let distances = hypernym_distances("rex", &synsets);
+
+        assert_eq!(distances["rex"], 0);
+        assert_eq!(distances["dog"], 1);
+        assert_eq!(distances["entity"], 3);
+        assert!(!distances.contains_key("cat"));
+    }
+
+    #[test]
+    fn max_depth_survives_cycles() {
+        let synsets: HashMap<String, Synset> = [synset("a", "n", &["b"]), synset("b", "n", &["a"])]
+            .into_iter()
+            .map(|synset| (synset.synset_id.clone(), synset))
+            .collect();
+
+        assert_eq!(max_depth("a", &synsets, &mut HashMap::new()), 2);
+    }
+}
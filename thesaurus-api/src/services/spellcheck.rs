@@ -0,0 +1,221 @@
+use crate::{
+    error::AppError,
+    models::word::Correction,
+    services::autocomplete::{self, Autocomplete},
+};
+
+pub const MAX_CORRECTIONS: usize = 5;
+
+const MAX_FUZZY_EDITS: u32 = 2;
+
+const KEYBOARD_ROWS: [(&str, f32); 3] =
+    [("qwertyuiop", 0.0), ("asdfghjkl", 0.25), ("zxcvbnm", 0.75)];
+
+/// Lemmas close to `word`, best first. Candidates come from the
+/// autocomplete FSTs, are kept within one typo (two for words longer than
+/// four letters) and are ranked by [`typo_distance`], then by how many
+/// senses they have.
+pub fn corrections(lang: &str, word: &str, limit: usize) -> Vec<Correction> {
+    match autocomplete::current() {
+        Some(autocomplete) => rank_corrections(&autocomplete, lang, word, limit),
+        None => Vec::new(),
+    }
+}
+
+fn rank_corrections(
+    autocomplete: &Autocomplete,
+    lang: &str,
+    word: &str,
+    limit: usize,
+) -> Vec<Correction> {
+    let word = word.trim().to_lowercase();
+    let max_distance = if word.chars().count() <= 4 { 1.0 } else { 2.0 };
+    let typed: Vec<char> = word.chars().collect();
+
+    // The automaton counts a swapped pair as two edits, so search wide and
+    // let typo_distance decide what is close enough.
+    let mut candidates: Vec<(Correction, u32)> = autocomplete
+        .fuzzy(lang, &word, MAX_FUZZY_EDITS)
+        .into_iter()
+        .filter(|candidate| candidate.word.to_lowercase() != word)
+        .map(|candidate| {
+            let lemma: Vec<char> = candidate.word.to_lowercase().chars().collect();
+            let correction = Correction {
+                distance: typo_distance(&typed, &lemma),
+                word: candidate.word,
+            };
+            (correction, candidate.senses)
+        })
+        .filter(|(correction, _)| correction.distance <= max_distance)
+        .collect();
+
+    candidates.sort_by(|(a, a_senses), (b, b_senses)| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| b_senses.cmp(a_senses))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+
+    candidates
+        .into_iter()
+        .take(limit)
+        .map(|(correction, _)| correction)
+        .collect()
+}
+
+/// The 404 for a lookup miss, with spelling corrections attached.
+pub fn word_not_found(word: &str, lang: &str) -> AppError {
+    AppError::WordNotFound {
+        word: word.to_string(),
+        suggestions: corrections(lang, word, MAX_CORRECTIONS),
+    }
+}
+
+fn key_position(c: char) -> Option<(f32, f32)> {
+    KEYBOARD_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row, (keys, offset))| {
+            keys.find(c)
+                .map(|column| (row as f32, column as f32 + offset))
+        })
+}
+
+/// Whether `a` and `b` are neighbouring keys on a QWERTY keyboard.
+fn adjacent(a: char, b: char) -> bool {
+    match (key_position(a), key_position(b)) {
+        (Some((row_a, x_a)), Some((row_b, x_b))) => {
+            a != b && (row_a - row_b).abs() <= 1.0 && (x_a - x_b).abs() <= 1.0
+        }
+        _ => false,
+    }
+}
+
+/// Edit distance tuned for typing mistakes. Hitting a neighbouring key,
+/// swapping two letters and doubling or undoubling a letter each cost half
+/// an edit; everything else costs one.
+fn typo_distance(typed: &[char], lemma: &[char]) -> f32 {
+    let (n, m) = (typed.len(), lemma.len());
+    let mut d = vec![vec![0.0f32; m + 1]; n + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i as f32;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j as f32;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = if typed[i - 1] == lemma[j - 1] {
+                0.0
+            } else if adjacent(typed[i - 1], lemma[j - 1]) {
+                0.5
+            } else {
+                1.0
+            };
+            // An extra typed letter that repeats its neighbour
+            let deletion = if i > 1 && typed[i - 1] == typed[i - 2] {
+                0.5
+            } else {
+                1.0
+            };
+            // A missed letter that repeats its neighbour
+            let insertion = if j > 1 && lemma[j - 1] == lemma[j - 2] {
+                0.5
+            } else {
+                1.0
+            };
+
+            let mut best = (d[i - 1][j - 1] + substitution)
+                .min(d[i - 1][j] + deletion)
+                .min(d[i][j - 1] + insertion);
+
+            if i > 1 && j > 1 && typed[i - 1] == lemma[j - 2] && typed[i - 2] == lemma[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 0.5);
+            }
+
+            d[i][j] = best;
+        }
+    }
+
+    d[n][m]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn distance(typed: &str, lemma: &str) -> f32 {
+        let typed: Vec<char> = typed.chars().collect();
+        let lemma: Vec<char> = lemma.chars().collect();
+        typo_distance(&typed, &lemma)
+    }
+
+    fn words(corrections: Vec<Correction>) -> Vec<String> {
+        corrections.into_iter().map(|c| c.word).collect()
+    }
+
+    #[test]
+    fn typo_distance_discounts_typing_mistakes() {
+        assert_eq!(distance("word", "word"), 0.0);
+        // Transposition
+        assert_eq!(distance("teh", "the"), 0.5);
+        // Neighbouring key
+        assert_eq!(distance("wprd", "word"), 0.5);
+        // Missed and extra doubled letters
+        assert_eq!(distance("accomodate", "accommodate"), 0.5);
+        assert_eq!(distance("untill", "until"), 0.5);
+        // Unrelated substitution, insertion and deletion
+        assert_eq!(distance("wxrd", "word"), 1.0);
+        assert_eq!(distance("wod", "word"), 1.0);
+        assert_eq!(distance("", "word"), 4.0);
+    }
+
+    #[test]
+    fn adjacent_follows_qwerty_rows() {
+        assert!(adjacent('q', 'w'));
+        assert!(adjacent('s', 'w'));
+        assert!(adjacent('b', 'g'));
+        assert!(!adjacent('q', 'p'));
+        assert!(!adjacent('a', 'a'));
+        assert!(!adjacent('a', '1'));
+    }
+
+    #[test]
+    fn rank_corrections_orders_by_distance_then_senses() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "the", "language": "en", "definitions": ["a"] },
+            { "word": "ten", "language": "en", "definitions": ["a", "b", "c"] },
+            { "word": "tea", "language": "en", "definitions": ["a", "b"] },
+            { "word": "toe", "language": "en", "definitions": ["a"] },
+        ]));
+
+        // "the" is a transposition and "ten" a neighbouring key, so the
+        // sense count decides between them. "tea" is a full substitution
+        // away and "toe" two, too far for a short word.
+        assert_eq!(
+            words(rank_corrections(&autocomplete, "en", "teh", 10)),
+            vec!["ten", "the", "tea"]
+        );
+        assert_eq!(
+            words(rank_corrections(&autocomplete, "en", "teh", 2)),
+            vec!["ten", "the"]
+        );
+    }
+
+    #[test]
+    fn rank_corrections_skips_the_word_itself() {
+        let autocomplete = Autocomplete::from_documents(json!([
+            { "word": "Bank", "language": "en" },
+            { "word": "band", "language": "en" },
+        ]));
+
+        assert_eq!(
+            words(rank_corrections(&autocomplete, "en", "bank", 5)),
+            vec!["band"]
+        );
+        assert!(rank_corrections(&autocomplete, "fr", "bank", 5).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    error::AppError,
+    models::{suggestion::Suggestion, user::User},
+};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub async fn list_suggestions(pool: &PgPool, user_id: &Uuid) -> Result<Vec<Suggestion>, AppError> {
+    let suggestions = sqlx::query_as::<_, Suggestion>(
+        "SELECT *, 'user' AS provenance FROM suggestions WHERE user_id = $1 \
+         ORDER BY created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suggestions)
+}
+
+/// Adds a suggestion. Suggesting the same relation twice returns the
+/// existing one.
+pub async fn add_suggestion(
+    pool: &PgPool,
+    user_id: &Uuid,
+    word: &str,
+    related_word: &str,
+    relation: &str,
+    language: &str,
+) -> Result<Suggestion, AppError> {
+    let suggestion = sqlx::query_as::<_, Suggestion>(
+        "INSERT INTO suggestions (id, user_id, word, related_word, relation, language) \
+         VALUES ($1, $2, $3, $4, $5, $6) \
+         ON CONFLICT (user_id, word, related_word, relation, language) \
+         DO UPDATE SET updated_at = suggestions.updated_at \
+         RETURNING *, 'user' AS provenance",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(word)
+    .bind(related_word)
+    .bind(relation)
+    .bind(language)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(suggestion)
+}
+
+pub async fn delete_suggestion(
+    pool: &PgPool,
+    user_id: &Uuid,
+    suggestion_id: &Uuid,
+) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM suggestions WHERE id = $1 AND user_id = $2")
+        .bind(suggestion_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Suggestion '{}' not found",
+            suggestion_id
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn set_show_community(pool: &PgPool, user_id: &Uuid, show: bool) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE users SET show_community_suggestions = $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(show)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Suggestions for `word` that `viewer` gets to see: their own and their
+/// organisation's (unless rejected), plus everyone's approved suggestions
+/// if they opted in. When several people suggested the same relation only
+/// the closest one is kept, so `user` wins over `organization` over
+/// `community`.
+pub async fn visible_suggestions(
+    pool: &PgPool,
+    viewer: &User,
+    word: &str,
+    lang: &str,
+) -> Result<Vec<Suggestion>, AppError> {
+    let suggestions = sqlx::query_as::<_, Suggestion>(
+        "SELECT s.*, CASE \
+             WHEN s.user_id = $3 THEN 'user' \
+             WHEN $4::uuid IS NOT NULL AND u.organization_id = $4 THEN 'organization' \
+             ELSE 'community' END AS provenance \
+         FROM suggestions s JOIN users u ON u.id = s.user_id \
+         WHERE lower(s.word) = lower($1) AND s.language = $2 AND ( \
+             (s.user_id = $3 AND s.status <> 'rejected') \
+             OR ($4::uuid IS NOT NULL AND u.organization_id = $4 AND s.status <> 'rejected') \
+             OR ($5 AND s.status = 'approved')) \
+         ORDER BY CASE \
+             WHEN s.user_id = $3 THEN 0 \
+             WHEN $4::uuid IS NOT NULL AND u.organization_id = $4 THEN 1 \
+             ELSE 2 END, s.created_at",
+    )
+    .bind(word)
+    .bind(lang)
+    .bind(viewer.id)
+    .bind(viewer.organization_id)
+    .bind(viewer.show_community_suggestions)
+    .fetch_all(pool)
+    .await?;
+
+    let mut seen = HashSet::new();
+
+    Ok(suggestions
+        .into_iter()
+        .filter(|s| seen.insert((s.relation.clone(), s.related_word.to_lowercase())))
+        .collect())
+}
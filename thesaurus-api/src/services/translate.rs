@@ -0,0 +1,213 @@
+use crate::{
+    error::AppError,
+    models::word::{Concept, Sense, Translation},
+    services::search::{get_word_senses, quote_filter_value},
+};
+use meilisearch_sdk::{client::Client as MeiliClient, search::SearchResults};
+use std::collections::HashMap;
+
+/// Looks up every sense of `word` in `from` and returns, per sense, the
+/// synsets in `to` that share its ILI. Senses without an equivalent are left
+/// out.
+pub async fn translate_word(
+    client: &MeiliClient,
+    word: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<Translation>, AppError> {
+    let senses: Vec<_> = get_word_senses(client, word, from)
+        .await?
+        .into_iter()
+        .flat_map(|group| group.senses)
+        .filter(|sense| sense.ili.is_some())
+        .collect();
+
+    if senses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut ilis: Vec<&str> = senses.iter().filter_map(|s| s.ili.as_deref()).collect();
+    ilis.sort();
+    ilis.dedup();
+
+    let concepts = get_concepts(client, &ilis).await?;
+
+    Ok(match_equivalents(senses, &concepts, to))
+}
+
+/// Pairs each sense with the synsets in `to` that its ILI concept lists.
+/// Senses whose concept has nothing in `to` are dropped.
+fn match_equivalents(
+    senses: Vec<Sense>,
+    concepts: &HashMap<String, Concept>,
+    to: &str,
+) -> Vec<Translation> {
+    senses
+        .into_iter()
+        .filter_map(|sense| {
+            let ili = sense.ili?;
+            let equivalents: Vec<_> = concepts
+                .get(&ili)?
+                .synsets
+                .iter()
+                .filter(|synset| synset.language == to && synset.synset_id != sense.synset_id)
+                .cloned()
+                .collect();
+
+            if equivalents.is_empty() {
+                return None;
+            }
+
+            Some(Translation {
+                sense_id: sense.sense_id,
+                synset_id: sense.synset_id,
+                ili,
+                pos: sense.pos,
+                definition: sense.definition,
+                equivalents,
+            })
+        })
+        .collect()
+}
+
+async fn get_concepts(
+    client: &MeiliClient,
+    ilis: &[&str],
+) -> Result<HashMap<String, Concept>, AppError> {
+    let index = client.index("concepts");
+
+    let quoted: Vec<String> = ilis.iter().map(|ili| quote_filter_value(ili)).collect();
+    let filter_str = format!("ili IN [{}]", quoted.join(", "));
+
+    let mut search_query = index.search();
+    search_query.with_filter(&filter_str).with_limit(ilis.len());
+
+    let search_results: SearchResults<Concept> = search_query.execute().await?;
+
+    Ok(search_results
+        .hits
+        .into_iter()
+        .map(|result| (result.result.ili.clone(), result.result))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sense(sense_id: &str, synset_id: &str, ili: Option<&str>) -> Sense {
+        serde_json::from_value(json!({
+            "id": sense_id,
+            "word": "bank",
+            "language": "en",
+            "lexicon": "oewn",
+            "word_id": "oewn-bank-n",
+            "sense_id": sense_id,
+            "synset_id": synset_id,
+            "ili": ili,
+            "sense_number": 1,
+            "pos": "n",
+            "definition": null,
+            "synonyms": [],
+            "antonyms": [],
+            "broader_terms": [],
+            "narrower_terms": [],
+            "related_terms": [],
+            "relations": [],
+            "examples": [],
+        }))
+        .unwrap()
+    }
+
+    fn concept(ili: &str, synsets: &[(&str, &str)]) -> (String, Concept) {
+        let concept = serde_json::from_value(json!({
+            "id": ili,
+            "ili": ili,
+            "languages": synsets.iter().map(|(_, language)| language).collect::<Vec<_>>(),
+            "synsets": synsets
+                .iter()
+                .map(|(synset_id, language)| json!({
+                    "synset_id": synset_id,
+                    "language": language,
+                    "lexicon": "omw",
+                    "pos": "n",
+                    "definition": null,
+                    "lemmas": [],
+                }))
+                .collect::<Vec<_>>(),
+        }))
+        .unwrap();
+
+        (ili.to_string(), concept)
+    }
+
+    fn equivalents(translation: &Translation) -> Vec<&str> {
+        translation
+            .equivalents
+            .iter()
+            .map(|synset| synset.synset_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn senses_map_to_synsets_sharing_their_ili() {
+        let concepts = HashMap::from([
+            concept(
+                "i1",
+                &[
+                    ("en-1", "en"),
+                    ("fr-1", "fr"),
+                    ("fr-2", "fr"),
+                    ("de-1", "de"),
+                ],
+            ),
+            concept("i2", &[("en-2", "en"), ("fr-3", "fr")]),
+        ]);
+        let senses = vec![
+            sense("s1", "en-1", Some("i1")),
+            sense("s2", "en-2", Some("i2")),
+        ];
+
+        let translations = match_equivalents(senses, &concepts, "fr");
+
+        assert_eq!(translations.len(), 2);
+        assert_eq!(translations[0].sense_id, "s1");
+        assert_eq!(translations[0].ili, "i1");
+        assert_eq!(equivalents(&translations[0]), vec!["fr-1", "fr-2"]);
+        assert_eq!(equivalents(&translations[1]), vec!["fr-3"]);
+    }
+
+    #[test]
+    fn senses_without_a_counterpart_are_left_out() {
+        let concepts = HashMap::from([
+            concept("i1", &[("en-1", "en"), ("de-1", "de")]),
+            concept("i2", &[("en-2", "en"), ("fr-2", "fr")]),
+        ]);
+        let senses = vec![
+            // Only German shares this concept.
+            sense("s1", "en-1", Some("i1")),
+            // No ILI at all.
+            sense("s2", "en-3", None),
+            // An ILI with no concept document.
+            sense("s3", "en-4", Some("i9")),
+            sense("s4", "en-2", Some("i2")),
+        ];
+
+        let translations = match_equivalents(senses, &concepts, "fr");
+
+        assert_eq!(translations.len(), 1);
+        assert_eq!(translations[0].sense_id, "s4");
+        assert!(match_equivalents(Vec::new(), &concepts, "fr").is_empty());
+    }
+
+    #[test]
+    fn a_sense_is_not_its_own_equivalent() {
+        let concepts = HashMap::from([concept("i1", &[("en-1", "en"), ("en-9", "en")])]);
+
+        let translations =
+            match_equivalents(vec![sense("s1", "en-1", Some("i1"))], &concepts, "en");
+
+        assert_eq!(equivalents(&translations[0]), vec!["en-9"]);
+    }
+}
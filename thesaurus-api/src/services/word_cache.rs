@@ -0,0 +1,225 @@
+use crate::models::word::Word;
+use lru::LruCache;
+use serde::Serialize;
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+struct Entry {
+    word: Option<Word>,
+    cached_at: Instant,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WordCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+    pub index_version: Option<String>,
+}
+
+/// An LRU cache of exact word lookups, misses included. Entries expire
+/// after `ttl` and the whole cache is dropped when the index version
+/// changes.
+pub struct WordCache {
+    entries: Mutex<LruCache<(String, String), Entry>>,
+    ttl: Duration,
+    index_version: Mutex<Option<String>>,
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl WordCache {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+            index_version: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// `Some(None)` is a cached "not found".
+    pub fn get(&self, word: &str, lang: &str) -> Option<Option<Word>> {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (word.to_string(), lang.to_string());
+
+        let cached = match entries.get(&key) {
+            Some(entry) if entry.cached_at.elapsed() < self.ttl => Some(entry.word.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        cached
+    }
+
+    /// Taken before a lookup and handed back to [`WordCache::insert`], so a
+    /// lookup that raced with a clear does not put stale data back.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn insert(&self, word: &str, lang: &str, value: Option<Word>, generation: u64) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if self.generation() != generation {
+            return;
+        }
+
+        entries.put(
+            (word.to_string(), lang.to_string()),
+            Entry {
+                word: value,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    pub fn invalidate(&self, word: &str, lang: &str) {
+        let mut entries = self.entries.lock().unwrap();
+
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.pop(&(word.to_string(), lang.to_string()));
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.clear();
+    }
+
+    /// Records the current index version, clearing the cache if it changed.
+    /// Returns whether it did.
+    pub fn set_index_version(&self, version: String) -> bool {
+        let changed = {
+            let mut current = self.index_version.lock().unwrap();
+
+            if current.as_deref() == Some(version.as_str()) {
+                return false;
+            }
+
+            current.replace(version).is_some()
+        };
+
+        if changed {
+            self.clear();
+        }
+
+        changed
+    }
+
+    pub fn stats(&self) -> WordCacheStats {
+        let index_version = self.index_version.lock().unwrap().clone();
+        let entries = self.entries.lock().unwrap();
+
+        WordCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: entries.len(),
+            capacity: entries.cap().get(),
+            index_version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> WordCache {
+        WordCache::new(NonZeroUsize::new(2).unwrap(), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn caches_misses_per_language() {
+        let cache = cache();
+
+        assert!(cache.get("bank", "en").is_none());
+        cache.insert("bank", "en", None, cache.generation());
+
+        assert!(matches!(cache.get("bank", "en"), Some(None)));
+        assert!(cache.get("bank", "fr").is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 1));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = cache();
+        let generation = cache.generation();
+
+        cache.insert("a", "en", None, generation);
+        cache.insert("b", "en", None, generation);
+        cache.get("a", "en");
+        cache.insert("c", "en", None, generation);
+
+        assert!(cache.get("a", "en").is_some());
+        assert!(cache.get("b", "en").is_none());
+        assert!(cache.get("c", "en").is_some());
+    }
+
+    #[test]
+    fn expires_entries_after_ttl() {
+        let cache = WordCache::new(NonZeroUsize::new(2).unwrap(), Duration::ZERO);
+        cache.insert("bank", "en", None, cache.generation());
+
+        assert!(cache.get("bank", "en").is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn drops_inserts_from_before_an_invalidation() {
+        let cache = cache();
+
+        // A lookup starts, then an edit invalidates the word before the
+        // lookup's result comes back.
+        let generation = cache.generation();
+        cache.invalidate("bank", "en");
+        cache.insert("bank", "en", None, generation);
+        assert!(cache.get("bank", "en").is_none());
+
+        cache.insert("bank", "en", None, cache.generation());
+        assert!(matches!(cache.get("bank", "en"), Some(None)));
+
+        cache.invalidate("bank", "en");
+        assert!(cache.get("bank", "en").is_none());
+    }
+
+    #[test]
+    fn clears_when_the_index_version_changes() {
+        let cache = cache();
+
+        assert!(!cache.set_index_version("1".to_string()));
+        cache.insert("bank", "en", None, cache.generation());
+
+        assert!(!cache.set_index_version("1".to_string()));
+        assert!(matches!(cache.get("bank", "en"), Some(None)));
+
+        let generation = cache.generation();
+        assert!(cache.set_index_version("2".to_string()));
+        assert!(cache.get("bank", "en").is_none());
+        assert_ne!(cache.generation(), generation);
+        assert_eq!(cache.stats().index_version.as_deref(), Some("2"));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    error::AppError,
+    models::word_list::{WordList, WordListEntry, WordListSummary, WordListWithEntries},
+};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+fn list_not_found(list_id: &Uuid) -> AppError {
+    AppError::NotFound(format!("Word list '{}' not found", list_id))
+}
+
+pub async fn list_word_lists(
+    pool: &PgPool,
+    user_id: &Uuid,
+) -> Result<Vec<WordListSummary>, AppError> {
+    let lists = sqlx::query_as::<_, WordListSummary>(
+        "SELECT l.id, l.name, l.description, l.share_token, l.created_at, l.updated_at, \
+             COUNT(e.id) AS entry_count \
+         FROM word_lists l \
+         LEFT JOIN word_list_entries e ON e.list_id = l.id \
+         WHERE l.user_id = $1 \
+         GROUP BY l.id \
+         ORDER BY l.updated_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(lists)
+}
+
+pub async fn create_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    name: &str,
+    description: Option<&str>,
+) -> Result<WordList, AppError> {
+    let list = sqlx::query_as::<_, WordList>(
+        "INSERT INTO word_lists (id, user_id, name, description) VALUES ($1, $2, $3, $4) \
+         RETURNING *",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(name)
+    .bind(description)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(list)
+}
+
+async fn get_owned_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+) -> Result<WordList, AppError> {
+    sqlx::query_as::<_, WordList>("SELECT * FROM word_lists WHERE id = $1 AND user_id = $2")
+        .bind(list_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| list_not_found(list_id))
+}
+
+async fn get_entries(pool: &PgPool, list_id: &Uuid) -> Result<Vec<WordListEntry>, AppError> {
+    let entries = sqlx::query_as::<_, WordListEntry>(
+        "SELECT id, word, note, position, created_at, updated_at FROM word_list_entries \
+         WHERE list_id = $1 ORDER BY position, created_at",
+    )
+    .bind(list_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+pub async fn get_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+) -> Result<WordListWithEntries, AppError> {
+    let list = get_owned_list(pool, user_id, list_id).await?;
+    let entries = get_entries(pool, &list.id).await?;
+
+    Ok(WordListWithEntries { list, entries })
+}
+
+/// Looks up a list by its public share token, for read-only access.
+pub async fn get_shared_word_list(
+    pool: &PgPool,
+    share_token: &str,
+) -> Result<WordListWithEntries, AppError> {
+    let list = sqlx::query_as::<_, WordList>("SELECT * FROM word_lists WHERE share_token = $1")
+        .bind(share_token)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Shared word list not found".to_string()))?;
+    let entries = get_entries(pool, &list.id).await?;
+
+    Ok(WordListWithEntries { list, entries })
+}
+
+pub async fn update_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+    name: Option<&str>,
+    description: Option<&str>,
+) -> Result<WordList, AppError> {
+    sqlx::query_as::<_, WordList>(
+        "UPDATE word_lists SET name = COALESCE($3, name), \
+             description = COALESCE($4, description), updated_at = NOW() \
+         WHERE id = $1 AND user_id = $2 RETURNING *",
+    )
+    .bind(list_id)
+    .bind(user_id)
+    .bind(name)
+    .bind(description)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| list_not_found(list_id))
+}
+
+pub async fn delete_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM word_lists WHERE id = $1 AND user_id = $2")
+        .bind(list_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(list_not_found(list_id));
+    }
+
+    Ok(())
+}
+
+/// Creates (or replaces) the list's public share token.
+pub async fn share_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+) -> Result<WordList, AppError> {
+    sqlx::query_as::<_, WordList>(
+        "UPDATE word_lists SET share_token = $3, updated_at = NOW() \
+         WHERE id = $1 AND user_id = $2 RETURNING *",
+    )
+    .bind(list_id)
+    .bind(user_id)
+    .bind(Uuid::new_v4().simple().to_string())
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| list_not_found(list_id))
+}
+
+pub async fn unshare_word_list(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+) -> Result<WordList, AppError> {
+    sqlx::query_as::<_, WordList>(
+        "UPDATE word_lists SET share_token = NULL, updated_at = NOW() \
+         WHERE id = $1 AND user_id = $2 RETURNING *",
+    )
+    .bind(list_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| list_not_found(list_id))
+}
+
+async fn touch_list(pool: &PgPool, list_id: &Uuid) -> Result<(), AppError> {
+    sqlx::query("UPDATE word_lists SET updated_at = NOW() WHERE id = $1")
+        .bind(list_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Appends a word to the end of the list.
+pub async fn add_entry(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+    word: &str,
+    note: Option<&str>,
+) -> Result<WordListEntry, AppError> {
+    let list = get_owned_list(pool, user_id, list_id).await?;
+
+    // Concurrent adds of the same word race to the unique (list_id, word)
+    // constraint; the loser gets the same error as a sequential duplicate.
+    let entry = sqlx::query_as::<_, WordListEntry>(
+        "INSERT INTO word_list_entries (id, list_id, word, note, position) \
+         VALUES ($1, $2, $3, $4, \
+             (SELECT COALESCE(MAX(position) + 1, 0) FROM word_list_entries WHERE list_id = $2)) \
+         ON CONFLICT (list_id, word) DO NOTHING \
+         RETURNING id, word, note, position, created_at, updated_at",
+    )
+    .bind(Uuid::new_v4())
+    .bind(list.id)
+    .bind(word)
+    .bind(note)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::ValidationError(format!("'{}' is already in this list", word)))?;
+
+    touch_list(pool, &list.id).await?;
+
+    Ok(entry)
+}
+
+pub async fn update_entry(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+    entry_id: &Uuid,
+    note: Option<&str>,
+) -> Result<WordListEntry, AppError> {
+    let list = get_owned_list(pool, user_id, list_id).await?;
+
+    let entry = sqlx::query_as::<_, WordListEntry>(
+        "UPDATE word_list_entries SET note = $3, updated_at = NOW() \
+         WHERE id = $1 AND list_id = $2 \
+         RETURNING id, word, note, position, created_at, updated_at",
+    )
+    .bind(entry_id)
+    .bind(list.id)
+    .bind(note)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Entry '{}' not found", entry_id)))?;
+
+    touch_list(pool, &list.id).await?;
+
+    Ok(entry)
+}
+
+pub async fn delete_entry(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+    entry_id: &Uuid,
+) -> Result<(), AppError> {
+    let list = get_owned_list(pool, user_id, list_id).await?;
+
+    let result = sqlx::query("DELETE FROM word_list_entries WHERE id = $1 AND list_id = $2")
+        .bind(entry_id)
+        .bind(list.id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Entry '{}' not found",
+            entry_id
+        )));
+    }
+
+    touch_list(pool, &list.id).await?;
+
+    Ok(())
+}
+
+/// Applies a drag-and-drop reorder. `entry_ids` must name every entry of the
+/// list exactly once.
+pub async fn reorder_entries(
+    pool: &PgPool,
+    user_id: &Uuid,
+    list_id: &Uuid,
+    entry_ids: &[Uuid],
+) -> Result<Vec<WordListEntry>, AppError> {
+    let list = get_owned_list(pool, user_id, list_id).await?;
+    let current: HashSet<Uuid> = get_entries(pool, &list.id)
+        .await?
+        .into_iter()
+        .map(|entry| entry.id)
+        .collect();
+    let requested: HashSet<Uuid> = entry_ids.iter().copied().collect();
+
+    if requested.len() != entry_ids.len() || requested != current {
+        return Err(AppError::ValidationError(
+            "entry_ids must contain every entry of the list exactly once".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for (position, entry_id) in entry_ids.iter().enumerate() {
+        sqlx::query(
+            "UPDATE word_list_entries SET position = $1, updated_at = NOW() \
+             WHERE id = $2 AND list_id = $3",
+        )
+        .bind(position as i32)
+        .bind(entry_id)
+        .bind(list.id)
+        .execute(&mut tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE word_lists SET updated_at = NOW() WHERE id = $1")
+        .bind(list.id)
+        .execute(&mut tx)
+        .await?;
+
+    tx.commit().await?;
+
+    get_entries(pool, &list.id).await
+}
+
+/// Renders a list as CSV with one row per entry.
+pub fn export_csv(list: &WordListWithEntries) -> String {
+    let mut csv = String::from("position,word,note,added_at\n");
+
+    for entry in &list.entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            entry.position + 1,
+            csv_field(&entry.word),
+            csv_field(entry.note.as_deref().unwrap_or("")),
+            entry.created_at.to_rfc3339()
+        ));
+    }
+
+    csv
+}
+
+/// Quotes `value` for a CSV cell. Cells a spreadsheet would read as a
+/// formula get a leading `'` so opening an export never runs one.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_separators_quotes_and_newlines() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("one\ntwo"), "\"one\ntwo\"");
+        assert_eq!(csv_field("one\r\ntwo"), "\"one\r\ntwo\"");
+    }
+
+    #[test]
+    fn csv_field_defuses_formulas() {
+        assert_eq!(csv_field("=1+1"), "'=1+1");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("\tx"), "'\tx");
+        assert_eq!(csv_field("\rx"), "\"'\rx\"");
+        assert_eq!(
+            csv_field("=HYPERLINK(\"x\",\"y\")"),
+            "\"'=HYPERLINK(\"\"x\"\",\"\"y\"\")\""
+        );
+        assert_eq!(csv_field("well-known"), "well-known");
+    }
+}
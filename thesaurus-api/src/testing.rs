@@ -0,0 +1,87 @@
+//! Helpers for tests that need Postgres. They run against
+//! `TEST_DATABASE_URL`, migrating it first, and are skipped when it is
+//! not set.
+
+use crate::{
+    config::Config,
+    db::AppState,
+    models::user::User,
+    services::{mailer::RecordingMailer, rate_limit},
+};
+use serde_json::json;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A pool on the test database, or `None` when there is none to use.
+pub async fn test_pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping");
+        return None;
+    };
+
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&url)
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    Some(pool)
+}
+
+/// A new user with a password and an unverified, unique email address.
+pub async fn create_user(pool: &PgPool) -> User {
+    let id = Uuid::new_v4();
+
+    sqlx::query_as::<_, User>(
+        "INSERT INTO users (id, name, email, password) VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(id)
+    .bind("Test User")
+    .bind(format!("{}@example.com", id.simple()))
+    .bind("not a real hash")
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+pub fn test_config() -> Config {
+    serde_json::from_value(json!({
+        "jwt_secret": "test-secret",
+        "jwt_expires_in": 15,
+        "jwt_maxage": 60,
+        "default_language": "en",
+        "history_retention_days": 90,
+        "refresh_token_days": 30,
+        "app_url": "http://localhost:3000",
+        "mailer": "file",
+        "mail_from": "Thesaurus <noreply@example.com>",
+        "smtp_port": 587,
+        "oidc_scopes": "openid email profile",
+        "rate_limit_backend": "memory",
+        "rate_limit_lookup": 120,
+        "rate_limit_auth": 10,
+        "rate_limit_account": 5,
+        "trust_proxy": false,
+        "word_cache_size": 1000,
+        "word_cache_ttl_secs": 300,
+        "http_cache_max_age": 300,
+    }))
+    .unwrap()
+}
+
+/// App state on `pool`, with a Meilisearch client that is never reached and
+/// a mailer that only records.
+pub fn test_state(pool: PgPool) -> AppState {
+    let config = test_config();
+
+    AppState {
+        rate_limiter: rate_limit::from_config(&config, &pool).unwrap(),
+        db: pool,
+        meili: meilisearch_sdk::Client::new("http://127.0.0.1:7700", Some("test")),
+        config,
+        mailer: Arc::new(RecordingMailer::default()),
+        oidc: None,
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::{Context, Result};
+use meilisearch_sdk::documents::DocumentsQuery;
+use meilisearch_sdk::indexes::Index;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{MeiliConcept, MeiliSense, MeiliSynset, MeiliWord};
+
+const FETCH_PAGE_SIZE: usize = 10_000;
+const SUMMARY_SAMPLE_SIZE: usize = 10;
+
+/// A document that carries a hash of its own content, so a re-import can
+/// tell which documents actually changed.
+pub trait HashedDocument: Serialize {
+    fn id(&self) -> &str;
+    fn content_hash(&self) -> &str;
+    fn set_content_hash(&mut self, hash: String);
+}
+
+impl HashedDocument for MeiliWord {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    fn set_content_hash(&mut self, hash: String) {
+        self.content_hash = hash;
+    }
+}
+
+impl HashedDocument for MeiliSense {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    fn set_content_hash(&mut self, hash: String) {
+        self.content_hash = hash;
+    }
+}
+
+impl HashedDocument for MeiliConcept {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    fn set_content_hash(&mut self, hash: String) {
+        self.content_hash = hash;
+    }
+}
+
+impl HashedDocument for MeiliSynset {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    fn set_content_hash(&mut self, hash: String) {
+        self.content_hash = hash;
+    }
+}
+
+/// Hashes every document's JSON representation (with an empty hash field)
+/// and stores the result on the document.
+pub fn assign_content_hashes<T: HashedDocument + Send>(documents: &mut [T]) {
+    documents.par_iter_mut().for_each(|doc| {
+        doc.set_content_hash(String::new());
+        let bytes = serde_json::to_vec(doc).expect("documents always serialize to JSON");
+        doc.set_content_hash(format!("{:x}", Sha256::digest(&bytes)));
+    });
+}
+
+#[derive(Deserialize)]
+struct IndexedDocument {
+    id: String,
+    #[serde(default)]
+    content_hash: Option<String>,
+}
+
+/// Fetches the id and content hash of every document already in the index.
+/// Documents imported before hashes existed map to an empty hash.
+pub async fn fetch_indexed_hashes(index: &Index) -> Result<HashMap<String, String>> {
+    if index.get_stats().await.is_err() {
+        println!("Index '{}' does not exist yet", index.uid);
+        return Ok(HashMap::new());
+    }
+
+    let mut hashes = HashMap::new();
+    let mut offset = 0;
+
+    loop {
+        let page = DocumentsQuery::new(index)
+            .with_fields(["id", "content_hash"])
+            .with_offset(offset)
+            .with_limit(FETCH_PAGE_SIZE)
+            .execute::<IndexedDocument>()
+            .await
+            .context(format!(
+                "Failed to fetch documents from index '{}'",
+                index.uid
+            ))?;
+
+        let fetched = page.results.len();
+        for doc in page.results {
+            hashes.insert(doc.id, doc.content_hash.unwrap_or_default());
+        }
+
+        offset += fetched;
+        if fetched < FETCH_PAGE_SIZE || offset >= page.total as usize {
+            break;
+        }
+    }
+
+    println!(
+        "Fetched {} existing document hashes from '{}'",
+        hashes.len(),
+        index.uid
+    );
+
+    Ok(hashes)
+}
+
+pub struct DocumentDiff<'a, T> {
+    pub added: Vec<&'a T>,
+    pub changed: Vec<&'a T>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl<'a, T: HashedDocument> DocumentDiff<'a, T> {
+    pub fn compute(existing: &HashMap<String, String>, documents: &'a [T]) -> Self {
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut unchanged = 0;
+
+        for doc in documents {
+            match existing.get(doc.id()) {
+                None => added.push(doc),
+                Some(hash) if hash != doc.content_hash() => changed.push(doc),
+                Some(_) => unchanged += 1,
+            }
+        }
+
+        let current_ids: HashSet<&str> = documents.iter().map(|doc| doc.id()).collect();
+        let mut removed: Vec<String> = existing
+            .keys()
+            .filter(|id| !current_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        removed.sort();
+
+        added.sort_by(|a, b| a.id().cmp(b.id()));
+        changed.sort_by(|a, b| a.id().cmp(b.id()));
+
+        Self {
+            added,
+            changed,
+            removed,
+            unchanged,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    /// Documents that need to be (re-)uploaded.
+    pub fn upserts(&self) -> Vec<&'a T> {
+        self.added
+            .iter()
+            .chain(self.changed.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn print_summary(&self, index_name: &str) {
+        println!("\nDiff summary for index '{}':", index_name);
+        println!("  Added:     {}", self.added.len());
+        println!("  Changed:   {}", self.changed.len());
+        println!("  Removed:   {}", self.removed.len());
+        println!("  Unchanged: {}", self.unchanged);
+
+        print_sample("Added", self.added.iter().map(|doc| doc.id()));
+        print_sample("Changed", self.changed.iter().map(|doc| doc.id()));
+        print_sample("Removed", self.removed.iter().map(String::as_str));
+    }
+}
+
+fn print_sample<'a>(label: &str, ids: impl ExactSizeIterator<Item = &'a str>) {
+    let total = ids.len();
+    if total == 0 {
+        return;
+    }
+
+    println!(
+        "  {} (showing {} of {}):",
+        label,
+        total.min(SUMMARY_SAMPLE_SIZE),
+        total
+    );
+    for id in ids.take(SUMMARY_SAMPLE_SIZE) {
+        println!("    {}", id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concept(id: &str, languages: &[&str]) -> MeiliConcept {
+        MeiliConcept {
+            id: id.to_string(),
+            ili: id.to_string(),
+            languages: languages.iter().map(|l| l.to_string()).collect(),
+            synsets: Vec::new(),
+            content_hash: String::new(),
+        }
+    }
+
+    fn hashed(mut documents: Vec<MeiliConcept>) -> Vec<MeiliConcept> {
+        assign_content_hashes(&mut documents);
+        documents
+    }
+
+    fn indexed(documents: &[MeiliConcept]) -> HashMap<String, String> {
+        documents
+            .iter()
+            .map(|doc| (doc.id.clone(), doc.content_hash.clone()))
+            .collect()
+    }
+
+    fn ids<'a>(documents: &[&'a MeiliConcept]) -> Vec<&'a str> {
+        documents.iter().map(|doc| doc.id.as_str()).collect()
+    }
+
+    #[test]
+    fn content_hash_is_stable_across_runs() {
+        let first = hashed(vec![concept("i1", &["en"])]);
+        // Hashing again must not be thrown off by the hash already present.
+        let second = hashed(first.clone());
+
+        assert_eq!(first[0].content_hash, second[0].content_hash);
+        // SHA-256 of {"id":"i1","ili":"i1","languages":["en"],"synsets":[],"content_hash":""}
+        assert_eq!(
+            first[0].content_hash,
+            "2a59166c311230343c378a62077ea49a574698dc2f7fb47d8b1615b5e026fa8f"
+        );
+    }
+
+    #[test]
+    fn content_hash_changes_with_content() {
+        let documents = hashed(vec![concept("i1", &["en"]), concept("i1", &["en", "fr"])]);
+
+        assert_ne!(documents[0].content_hash, documents[1].content_hash);
+    }
+
+    #[test]
+    fn compute_sorts_documents_into_added_changed_unchanged_and_removed() {
+        let previous = hashed(vec![
+            concept("i1", &["en"]),
+            concept("i2", &["en"]),
+            concept("i3", &["en"]),
+        ]);
+        let current = hashed(vec![
+            concept("i1", &["en"]),
+            concept("i2", &["en", "fr"]),
+            concept("i4", &["en"]),
+        ]);
+        let existing = indexed(&previous);
+
+        let diff = DocumentDiff::compute(&existing, &current);
+
+        assert_eq!(ids(&diff.added), ["i4"]);
+        assert_eq!(ids(&diff.changed), ["i2"]);
+        assert_eq!(diff.removed, ["i3"]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(ids(&diff.upserts()), ["i4", "i2"]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn compute_treats_documents_without_a_hash_as_changed() {
+        let current = hashed(vec![concept("i1", &["en"])]);
+        let existing = HashMap::from([("i1".to_string(), String::new())]);
+
+        let diff = DocumentDiff::compute(&existing, &current);
+
+        assert_eq!(ids(&diff.changed), ["i1"]);
+    }
+
+    #[test]
+    fn compute_is_empty_when_nothing_changed() {
+        let current = hashed(vec![concept("i1", &["en"]), concept("i2", &["fr"])]);
+
+        let diff = DocumentDiff::compute(&indexed(&current), &current);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+}
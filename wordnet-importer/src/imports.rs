@@ -0,0 +1,47 @@
+use anyhow::Result;
+use meilisearch_sdk::client::Client;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Index with one marker document per live index, replaced after every
+/// import. `thesaurus-api` watches it to tell an import apart from its own
+/// edits to the `words` index.
+const IMPORTS_INDEX: &str = "imports";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Serialize)]
+struct ImportMarker {
+    /// Uid of the live index that was imported into
+    id: String,
+    imported_at: u64,
+}
+
+/// Records that the data in `lives` has just been replaced.
+pub async fn record_import(client: &Client, lives: &[&str]) -> Result<()> {
+    let imported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let markers: Vec<ImportMarker> = lives
+        .iter()
+        .map(|live| ImportMarker {
+            id: live.to_string(),
+            imported_at,
+        })
+        .collect();
+
+    let task = client
+        .index(IMPORTS_INDEX)
+        .add_or_replace(&markers, Some("id"))
+        .await?
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await?;
+
+    if task.is_failure() {
+        anyhow::bail!("Failed to record the import: {}", task.unwrap_failure());
+    }
+
+    Ok(())
+}
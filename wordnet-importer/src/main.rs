@@ -3,21 +3,58 @@ use clap::Parser;
 use indicatif::{ProgressBar, ProgressStyle};
 use reqwest::Client;
 use std::path::PathBuf;
-use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 
+mod diff;
+mod imports;
 mod models;
 mod parser;
+mod swap;
+mod tasks;
 mod transform;
 
-use models::MeiliWord;
+use diff::{DocumentDiff, HashedDocument, assign_content_hashes, fetch_indexed_hashes};
 use parser::parse_wordnet_xml;
-use transform::transform_to_meilisearch;
+use serde::Serialize;
+use transform::{
+    transform_to_concepts, transform_to_meilisearch, transform_to_senses, transform_to_synsets,
+};
 
 const WORDNET_URL: &str = "https://en-word.net/static/english-wordnet-2024.xml.gz";
 const BATCH_SIZE: usize = 1000;
 
+/// Index settings applied before documents are uploaded.
+struct IndexSettings {
+    searchable: &'static [&'static str],
+    filterable: &'static [&'static str],
+    sortable: &'static [&'static str],
+}
+
+const WORD_INDEX_SETTINGS: IndexSettings = IndexSettings {
+    searchable: &["word", "definitions", "synonyms", "antonyms", "examples"],
+    filterable: &["pos", "word", "language", "lexicon", "relations.rel_type"],
+    sortable: &["word"],
+};
+
+const SENSE_INDEX_SETTINGS: IndexSettings = IndexSettings {
+    searchable: &["word", "definition", "synonyms", "examples"],
+    filterable: &["word", "word_id", "pos", "synset_id", "language", "lexicon"],
+    sortable: &["word", "sense_number"],
+};
+
+const SYNSET_INDEX_SETTINGS: IndexSettings = IndexSettings {
+    searchable: &["lemmas", "definition"],
+    filterable: &["synset_id", "ili", "pos", "language", "lexicon"],
+    sortable: &[],
+};
+
+const CONCEPT_INDEX_SETTINGS: IndexSettings = IndexSettings {
+    searchable: &["synsets.lemmas", "synsets.definition"],
+    filterable: &["ili", "languages"],
+    sortable: &[],
+};
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -33,19 +70,68 @@ struct Args {
     #[arg(long, default_value = "words")]
     index: String,
 
-    /// Path to WordNet XML file (if not provided, will download the latest)
+    /// Meilisearch index name for per-sense documents
+    #[arg(long, default_value = "senses")]
+    senses_index: String,
+
+    /// Meilisearch index name for synsets and their relation graph
+    #[arg(long, default_value = "synsets")]
+    synsets_index: String,
+
+    /// Meilisearch index name for cross-lingual ILI concepts
+    #[arg(long, default_value = "concepts")]
+    concepts_index: String,
+
+    /// Path to WordNet XML file, plain or .xml.gz (if not provided, will download the latest)
     #[arg(long)]
     xml_path: Option<PathBuf>,
 
     /// Skip the Meilisearch upload (just process the XML)
     #[arg(long)]
     skip_upload: bool,
+
+    /// Only upload changed documents and delete ones no longer in WordNet
+    #[arg(long)]
+    diff: bool,
+
+    /// With --diff, print what would change without touching the index
+    #[arg(long, requires = "diff")]
+    dry_run: bool,
+
+    /// Build into timestamped staging indexes and swap them in once verified
+    #[arg(long, conflicts_with = "diff")]
+    swap: bool,
+
+    /// Number of previous indexes to keep around for rollback after a swap
+    #[arg(long, default_value_t = 1)]
+    keep_indexes: usize,
+
+    /// Swap the live indexes back to the ones replaced by the last --swap import
+    #[arg(long, conflicts_with_all = ["diff", "swap", "skip_upload"])]
+    rollback: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
+    let lives = [
+        args.index.as_str(),
+        &args.senses_index,
+        &args.synsets_index,
+        &args.concepts_index,
+    ];
+
+    if args.rollback {
+        let client =
+            meilisearch_sdk::client::Client::new(&args.meili_url, args.meili_key.as_deref());
+        println!("Rolling back to the previous indexes...");
+        swap::rollback(&client, &lives).await?;
+        imports::record_import(&client, &lives).await?;
+        println!("Done!");
+        return Ok(());
+    }
+
     let (xml_path, _temp_dir) = match &args.xml_path {
         Some(path) => (path.clone(), None),
         None => {
@@ -63,20 +149,179 @@ async fn main() -> Result<()> {
         wordnet_data.lexical_entries.len()
     );
 
+    let mut lexicons: Vec<_> = wordnet_data.lexicons.values().collect();
+    lexicons.sort_by(|a, b| a.id.cmp(&b.id));
+    for lexicon in lexicons {
+        println!(
+            "  Lexicon {} ({}) - Language: {}",
+            lexicon.id, lexicon.label, lexicon.language
+        );
+    }
+
     println!("Transforming data for Meilisearch...");
-    let meili_docs = transform_to_meilisearch(&wordnet_data);
+    let mut meili_docs = transform_to_meilisearch(&wordnet_data);
+    assign_content_hashes(&mut meili_docs);
 
     println!("Created {} documents for Meilisearch", meili_docs.len());
 
-    if !args.skip_upload {
-        println!("Uploading to Meilisearch...");
-        upload_to_meilisearch(
-            &args.meili_url,
-            args.meili_key.as_deref(),
-            &args.index,
-            &meili_docs,
+    println!("Transforming senses for Meilisearch...");
+    let mut sense_docs = transform_to_senses(&wordnet_data);
+    assign_content_hashes(&mut sense_docs);
+
+    println!(
+        "Created {} sense documents for Meilisearch",
+        sense_docs.len()
+    );
+
+    println!("Transforming synsets for Meilisearch...");
+    let mut synset_docs = transform_to_synsets(&wordnet_data);
+    assign_content_hashes(&mut synset_docs);
+
+    println!(
+        "Created {} synset documents for Meilisearch",
+        synset_docs.len()
+    );
+
+    println!("Building ILI concepts for Meilisearch...");
+    let mut concept_docs = transform_to_concepts(&wordnet_data);
+    assign_content_hashes(&mut concept_docs);
+
+    println!(
+        "Created {} concept documents for Meilisearch",
+        concept_docs.len()
+    );
+
+    if !args.skip_upload && args.swap {
+        let client =
+            meilisearch_sdk::client::Client::new(&args.meili_url, args.meili_key.as_deref());
+
+        let words_staging = swap::staging_index_name(&args.index);
+        let senses_staging = swap::staging_index_name(&args.senses_index);
+        let synsets_staging = swap::staging_index_name(&args.synsets_index);
+        let concepts_staging = swap::staging_index_name(&args.concepts_index);
+
+        let built = async {
+            println!("Building words into staging index '{}'...", words_staging);
+            build_staging_index(
+                &args,
+                &client,
+                &words_staging,
+                &WORD_INDEX_SETTINGS,
+                &meili_docs,
+                swap::sample_words(meili_docs.iter().map(|doc| doc.word.as_str())),
+            )
+            .await?;
+
+            println!("Building senses into staging index '{}'...", senses_staging);
+            build_staging_index(
+                &args,
+                &client,
+                &senses_staging,
+                &SENSE_INDEX_SETTINGS,
+                &sense_docs,
+                swap::sample_words(sense_docs.iter().map(|doc| doc.word.as_str())),
+            )
+            .await?;
+
+            println!(
+                "Building synsets into staging index '{}'...",
+                synsets_staging
+            );
+            build_staging_index(
+                &args,
+                &client,
+                &synsets_staging,
+                &SYNSET_INDEX_SETTINGS,
+                &synset_docs,
+                Vec::new(),
+            )
+            .await?;
+
+            println!(
+                "Building concepts into staging index '{}'...",
+                concepts_staging
+            );
+            build_staging_index(
+                &args,
+                &client,
+                &concepts_staging,
+                &CONCEPT_INDEX_SETTINGS,
+                &concept_docs,
+                Vec::new(),
+            )
+            .await?;
+
+            anyhow::Ok(())
+        }
+        .await;
+
+        if let Err(e) = built {
+            swap::discard_staging_indexes(
+                &client,
+                &[
+                    &words_staging,
+                    &senses_staging,
+                    &synsets_staging,
+                    &concepts_staging,
+                ],
+            )
+            .await;
+            return Err(e);
+        }
+
+        swap::swap_in(
+            &client,
+            &[
+                (args.index.clone(), words_staging),
+                (args.senses_index.clone(), senses_staging),
+                (args.synsets_index.clone(), synsets_staging),
+                (args.concepts_index.clone(), concepts_staging),
+            ],
         )
         .await?;
+
+        swap::prune_previous_indexes(&client, &args.index, args.keep_indexes).await?;
+        swap::prune_previous_indexes(&client, &args.senses_index, args.keep_indexes).await?;
+        swap::prune_previous_indexes(&client, &args.synsets_index, args.keep_indexes).await?;
+        swap::prune_previous_indexes(&client, &args.concepts_index, args.keep_indexes).await?;
+
+        imports::record_import(&client, &lives).await?;
+    } else if !args.skip_upload {
+        println!("Importing words into Meilisearch...");
+        import_index(&args, &args.index, &WORD_INDEX_SETTINGS, &meili_docs).await?;
+
+        println!("Importing senses into Meilisearch...");
+        import_index(
+            &args,
+            &args.senses_index,
+            &SENSE_INDEX_SETTINGS,
+            &sense_docs,
+        )
+        .await?;
+
+        println!("Importing synsets into Meilisearch...");
+        import_index(
+            &args,
+            &args.synsets_index,
+            &SYNSET_INDEX_SETTINGS,
+            &synset_docs,
+        )
+        .await?;
+
+        println!("Importing concepts into Meilisearch...");
+        import_index(
+            &args,
+            &args.concepts_index,
+            &CONCEPT_INDEX_SETTINGS,
+            &concept_docs,
+        )
+        .await?;
+
+        if !args.dry_run {
+            let client =
+                meilisearch_sdk::client::Client::new(&args.meili_url, args.meili_key.as_deref());
+            imports::record_import(&client, &lives).await?;
+        }
     }
 
     println!("Done!");
@@ -107,7 +352,7 @@ async fn download_wordnet() -> Result<(PathBuf, tempfile::TempDir)> {
             .progress_chars("#>-"),
     );
 
-    let gz_path = temp_dir.path().join("wordnet.gz");
+    let gz_path = temp_dir.path().join("english-wordnet-2024.xml.gz");
     let mut file = File::create(&gz_path).await?;
     let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
@@ -121,118 +366,210 @@ async fn download_wordnet() -> Result<(PathBuf, tempfile::TempDir)> {
 
     pb.finish_with_message("Download complete");
 
-    println!("Extracting gzip file...");
+    file.flush().await?;
+    drop(file);
 
-    let xml_path = temp_dir.path().join("english-wordnet-2024.xml");
+    if !gz_path.exists() {
+        anyhow::bail!("Downloaded file doesn't exist at {}", gz_path.display());
+    }
+
+    // The parser reads gzip input directly, so there is no need to extract
+    // the archive to disk first.
+    println!("Downloaded to {}", gz_path.display());
+
+    Ok((gz_path, temp_dir))
+}
+
+/// Uploads every document into a fresh staging index and verifies it. The
+/// live index is left untouched if anything goes wrong, and the caller
+/// deletes the staging index.
+async fn build_staging_index<T: Serialize>(
+    args: &Args,
+    client: &meilisearch_sdk::client::Client,
+    staging: &str,
+    settings: &IndexSettings,
+    documents: &[T],
+    sample_words: Vec<String>,
+) -> Result<()> {
+    swap::create_index(client, staging).await?;
+
+    upload_to_meilisearch(
+        &args.meili_url,
+        args.meili_key.as_deref(),
+        staging,
+        settings,
+        documents,
+    )
+    .await?;
+
+    swap::wait_for_index_idle(client, staging).await?;
+
+    swap::sanity_check(client, staging, documents.len(), &sample_words)
+        .await
+        .context(format!(
+            "Sanity checks failed for staging index '{}'",
+            staging
+        ))
+}
 
-    let gz_file = std::fs::File::open(&gz_path)
-        .context(format!("Failed to open gz file at {}", gz_path.display()))?;
+/// Uploads every document, or with `--diff` only the documents that differ
+/// from what is currently indexed.
+async fn import_index<T: HashedDocument + Sync>(
+    args: &Args,
+    index_name: &str,
+    settings: &IndexSettings,
+    documents: &[T],
+) -> Result<()> {
+    if !args.diff {
+        println!(
+            "Uploading {} documents to '{}'",
+            documents.len(),
+            index_name
+        );
+        return upload_to_meilisearch(
+            &args.meili_url,
+            args.meili_key.as_deref(),
+            index_name,
+            settings,
+            documents,
+        )
+        .await;
+    }
 
-    let mut gz_decoder = flate2::read::GzDecoder::new(gz_file);
-    let mut xml_file = std::fs::File::create(&xml_path).context(format!(
-        "Failed to create XML file at {}",
-        xml_path.display()
-    ))?;
+    let client = meilisearch_sdk::client::Client::new(&args.meili_url, args.meili_key.as_deref());
+    let index = client.index(index_name);
 
-    std::io::copy(&mut gz_decoder, &mut xml_file).context("Failed to decompress gzip file")?;
+    let existing = fetch_indexed_hashes(&index).await?;
+    let diff = DocumentDiff::compute(&existing, documents);
+    diff.print_summary(index_name);
 
-    drop(xml_file);
+    if args.dry_run {
+        println!("Dry run: no changes applied to '{}'", index_name);
+        return Ok(());
+    }
 
-    println!("Extracted to {}", xml_path.display());
+    if diff.is_empty() {
+        println!("Index '{}' is already up to date", index_name);
+        return Ok(());
+    }
 
+    let upserts = diff.upserts();
     println!(
-        "Listing directory contents of {}:",
-        temp_dir.path().display()
+        "Uploading {} new or changed documents to '{}'",
+        upserts.len(),
+        index_name
     );
-    for entry in std::fs::read_dir(temp_dir.path())? {
-        let entry = entry?;
-        println!("  {}", entry.path().display());
-    }
+    upload_to_meilisearch(
+        &args.meili_url,
+        args.meili_key.as_deref(),
+        index_name,
+        settings,
+        &upserts,
+    )
+    .await?;
+
+    delete_from_meilisearch(&index, &diff.removed).await
+}
 
-    if !xml_path.exists() {
-        anyhow::bail!("Extracted file doesn't exist at {}", xml_path.display());
+async fn delete_from_meilisearch(
+    index: &meilisearch_sdk::indexes::Index,
+    ids: &[String],
+) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
     }
 
-    Ok((xml_path, temp_dir))
+    println!(
+        "Deleting {} stale documents from '{}'",
+        ids.len(),
+        index.uid
+    );
+
+    let failures = tasks::run_batches(&index.client, "deletion", ids, BATCH_SIZE, |chunk| {
+        index.delete_documents(chunk)
+    })
+    .await;
+
+    tasks::ensure_no_failures(
+        &index.uid,
+        "deletion",
+        ids.len().div_ceil(BATCH_SIZE),
+        &failures,
+    )
 }
 
-async fn upload_to_meilisearch(
+async fn upload_to_meilisearch<T: Serialize>(
     url: &str,
     api_key: Option<&str>,
     index_name: &str,
-    documents: &[MeiliWord],
+    settings: &IndexSettings,
+    documents: &[T],
 ) -> Result<()> {
     let client = meilisearch_sdk::client::Client::new(url, api_key);
 
-    let mut index = client.index(index_name);
-
-    match index.get_primary_key().await {
-        Ok(primary_key) => {
-            if primary_key.is_none() || primary_key != Some("id") {
-                println!("Setting primary key to 'id'");
-                index.set_primary_key("id").await?;
-            }
-        }
+    let mut index = match client.get_index(index_name).await {
+        Ok(index) => index,
         Err(_) => {
-            println!("Setting primary key to 'id'");
-            index.set_primary_key("id").await?;
+            swap::create_index(&client, index_name).await?;
+            client.index(index_name)
         }
+    };
+
+    if index.get_primary_key().await?.is_none() {
+        println!("Setting primary key to 'id'");
+        tasks::apply_setting(&client, "primary key", index.set_primary_key("id")).await?;
     }
 
     println!("Configuring index settings...");
 
     println!("Setting searchable attributes");
-    index
-        .set_searchable_attributes(&["word", "definitions", "synonyms", "antonyms", "examples"])
-        .await?;
+    tasks::apply_setting(
+        &client,
+        "searchable attributes",
+        index.set_searchable_attributes(settings.searchable),
+    )
+    .await?;
 
     println!("Setting filterable attributes");
-    index.set_filterable_attributes(&["pos", "word"]).await?;
+    tasks::apply_setting(
+        &client,
+        "filterable attributes",
+        index.set_filterable_attributes(settings.filterable),
+    )
+    .await?;
 
     println!("Setting sortable attributes");
-    index.set_sortable_attributes(&["word"]).await?;
+    tasks::apply_setting(
+        &client,
+        "sortable attributes",
+        index.set_sortable_attributes(settings.sortable),
+    )
+    .await?;
 
     println!("Setting ranking rules");
-    index
-        .set_ranking_rules(&[
+    tasks::apply_setting(
+        &client,
+        "ranking rules",
+        index.set_ranking_rules(&[
             "words",
             "typo",
             "proximity",
             "attribute",
             "sort",
             "exactness",
-        ])
-        .await?;
-
-    let total_batches = documents.len().div_ceil(BATCH_SIZE);
-    let pb = ProgressBar::new(total_batches as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} batches ({eta})")?
-            .progress_chars("#>-"),
-    );
-
-    for (i, chunk) in documents.chunks(BATCH_SIZE).enumerate() {
-        let batch_num = i + 1;
-
-        match index.add_documents(chunk, Some("id")).await {
-            Ok(task) => {
-                println!(
-                    "Batch {} uploaded successfully. Task ID: {}",
-                    batch_num, task.task_uid
-                );
-            }
-            Err(e) => {
-                println!("Warning: Failed to upload batch {}: {}", batch_num, e);
-            }
-        }
-
-        tokio::time::sleep(Duration::from_millis(100)).await;
-
-        pb.inc(1);
-    }
-
-    pb.finish_with_message("Upload complete");
-
-    Ok(())
+        ]),
+    )
+    .await?;
+
+    let failures = tasks::run_batches(&client, "upload", documents, BATCH_SIZE, |chunk| {
+        index.add_documents(chunk, Some("id"))
+    })
+    .await;
+
+    tasks::ensure_no_failures(
+        index_name,
+        "upload",
+        documents.len().div_ceil(BATCH_SIZE),
+        &failures,
+    )
 }
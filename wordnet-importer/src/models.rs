@@ -1,27 +1,52 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct WordNetData {
+    pub lexicons: HashMap<String, Lexicon>,
     pub synsets: HashMap<String, Synset>,
     pub lexical_entries: HashMap<String, LexicalEntry>,
     pub senses: HashMap<String, Sense>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl WordNetData {
+    /// Language code of the lexicon with the given id, e.g. `en` for `oewn`.
+    pub fn language_of(&self, lexicon_id: &str) -> &str {
+        self.lexicons
+            .get(lexicon_id)
+            .map(|lexicon| lexicon.language.as_str())
+            .unwrap_or("unknown")
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct Lexicon {
+    pub id: String,
+    pub label: String,
+    pub language: String,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct LexicalEntry {
     pub id: String,
+    pub lexicon: String,
     pub lemma: Lemma,
+    pub forms: Vec<String>,  // Inflected and variant spellings from <Form>
     pub senses: Vec<String>, // Sense IDs
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct Lemma {
     pub written_form: String,
     pub part_of_speech: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct Sense {
     pub id: String,
     pub synset_id: String,
@@ -29,15 +54,18 @@ pub struct Sense {
     pub examples: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct SenseRelation {
     pub rel_type: String,
     pub target: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct Synset {
     pub id: String,
+    pub lexicon: String,
     pub ili: Option<String>,
     pub part_of_speech: String,
     pub definition: Option<String>,
@@ -46,7 +74,8 @@ pub struct Synset {
     pub members: Vec<String>, // Sense IDs
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[cfg_attr(test, derive(Serialize))]
 pub struct SynsetRelation {
     pub rel_type: String,
     pub target: String,
@@ -56,6 +85,8 @@ pub struct SynsetRelation {
 pub struct MeiliWord {
     pub id: String,
     pub word: String,
+    pub language: String,
+    pub lexicon: String,
     pub definitions: Vec<String>,
     pub pos: Vec<String>,
     pub synonyms: Vec<String>,
@@ -63,5 +94,82 @@ pub struct MeiliWord {
     pub broader_terms: Vec<String>,
     pub narrower_terms: Vec<String>,
     pub related_terms: Vec<String>,
+    pub relations: Vec<TypedRelation>,
+    pub examples: Vec<String>,
+    pub forms: Vec<String>,
+    pub content_hash: String,
+}
+
+/// A related lemma together with the original WordNet `relType` and the
+/// coarse relation list it was folded into, which the API reads back.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TypedRelation {
+    pub rel_type: String,
+    pub bucket: String,
+    pub word: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MeiliSense {
+    pub id: String,
+    pub word: String,
+    pub language: String,
+    pub lexicon: String,
+    pub word_id: String,
+    pub sense_id: String,
+    pub synset_id: String,
+    pub ili: Option<String>,
+    pub sense_number: usize,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+    pub broader_terms: Vec<String>,
+    pub narrower_terms: Vec<String>,
+    pub related_terms: Vec<String>,
+    pub relations: Vec<TypedRelation>,
     pub examples: Vec<String>,
+    pub content_hash: String,
+}
+
+/// All synsets across the loaded lexicons that share one Interlingual Index
+/// entry, i.e. the same concept in different languages.
+#[derive(Debug, Serialize, Clone)]
+pub struct MeiliConcept {
+    pub id: String,
+    pub ili: String,
+    pub languages: Vec<String>,
+    pub synsets: Vec<ConceptSynset>,
+    pub content_hash: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConceptSynset {
+    pub synset_id: String,
+    pub language: String,
+    pub lexicon: String,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+}
+
+/// A synset with its typed outgoing relations, for graph traversal.
+#[derive(Debug, Serialize, Clone)]
+pub struct MeiliSynset {
+    pub id: String,
+    pub synset_id: String,
+    pub language: String,
+    pub lexicon: String,
+    pub ili: Option<String>,
+    pub pos: String,
+    pub definition: Option<String>,
+    pub lemmas: Vec<String>,
+    pub relations: Vec<SynsetEdge>,
+    pub content_hash: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SynsetEdge {
+    pub rel_type: String,
+    pub target: String,
 }
@@ -1,391 +1,544 @@
 use anyhow::{Context, Result};
-use roxmltree::{Document, Node};
+use flate2::bufread::GzDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
-use tokio::fs;
 
 use crate::models::{
-    Lemma, LexicalEntry, Sense, SenseRelation, Synset, SynsetRelation, WordNetData,
+    Lemma, LexicalEntry, Lexicon, Sense, SenseRelation, Synset, SynsetRelation, WordNetData,
 };
 
+const READ_BUFFER_SIZE: usize = 256 * 1024;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Parses a WordNet LMF file, plain or gzip-compressed, without loading the
+/// whole document into memory.
 pub async fn parse_wordnet_xml(path: &Path) -> Result<WordNetData> {
-    println!("Opening WordNet XML file at {}", path.display());
+    let path: PathBuf = path.to_path_buf();
 
-    let start_time = Instant::now();
+    tokio::task::spawn_blocking(move || parse_wordnet_file(&path))
+        .await
+        .context("XML parsing task panicked")?
+}
+
+pub fn parse_wordnet_file(path: &Path) -> Result<WordNetData> {
+    println!("Opening WordNet XML file at {}", path.display());
 
     if !path.exists() {
         return Err(anyhow::anyhow!("File does not exist: {}", path.display()));
     }
 
-    let content = match fs::read(path).await {
-        Ok(content) => {
-            println!("Successfully read file with size: {} bytes", content.len());
-            content
-        }
-        Err(e) => {
-            println!("Error reading file: {}", e);
-            return Err(anyhow::anyhow!("Failed to read XML file: {}", e));
-        }
-    };
-
-    println!("Parsing XML document...");
-    let xml_string = String::from_utf8_lossy(&content);
-    let options = roxmltree::ParsingOptions {
-        allow_dtd: true,
-        ..roxmltree::ParsingOptions::default()
-    };
-    let doc = match Document::parse_with_options(&xml_string, options) {
-        Ok(doc) => doc,
-        Err(e) => {
-            println!("Error parsing XML: {}", e);
-            return Err(anyhow::anyhow!("Failed to parse XML: {}", e));
-        }
-    };
+    let file = File::open(path).context(format!("Failed to open {}", path.display()))?;
+    let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
 
-    println!(
-        "XML document parsed successfully in {:.2?}",
-        start_time.elapsed()
-    );
+    let is_gzip = reader
+        .fill_buf()
+        .context("Failed to read XML file")?
+        .starts_with(&GZIP_MAGIC);
 
-    println!("Analyzing document structure...");
-    let root = doc.root();
-    let mut found_lexical_resource = false;
-    let mut found_lexicon = false;
-
-    let lexical_resource = doc
-        .root()
-        .children()
-        .find(|n| n.is_element() && n.tag_name().name() == "LexicalResource");
-
-    if let Some(lr) = lexical_resource {
-        found_lexical_resource = true;
-        println!("Found LexicalResource element");
-
-        let lexicons: Vec<_> = lr
-            .children()
-            .filter(|n| n.is_element() && n.tag_name().name() == "Lexicon")
-            .collect();
-
-        if !lexicons.is_empty() {
-            found_lexicon = true;
-            println!("Found {} Lexicon elements", lexicons.len());
-        } else {
-            println!("No Lexicon elements found under LexicalResource");
-        }
+    if is_gzip {
+        println!("Detected gzip-compressed input, decompressing on the fly");
+        let decoder = GzDecoder::new(reader);
+        parse_wordnet_reader(BufReader::with_capacity(READ_BUFFER_SIZE, decoder))
     } else {
-        println!("LexicalResource element not found");
+        parse_wordnet_reader(reader)
     }
+}
 
-    if !found_lexical_resource || !found_lexicon {
-        println!("\nDocument structure preview:");
-        let mut count = 0;
-        for node in doc.root().descendants().filter(|n| n.is_element()) {
-            if count < 10 {
-                println!("  Element: <{}>", node.tag_name().name());
-                for attr in node.attributes() {
-                    println!("    Attribute: {}=\"{}\"", attr.name(), attr.value());
-                }
-                count += 1;
-            } else {
-                break;
-            }
-        }
-    }
+/// Which element's character data is currently being collected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextTarget {
+    SenseExample,
+    SynsetDefinition,
+    SynsetIliDefinition,
+    SynsetExample,
+}
 
-    let mut synsets = HashMap::new();
-    let mut lexical_entries = HashMap::new();
-    let mut senses = HashMap::new();
+#[derive(Default)]
+struct ParseState {
+    lexicons: HashMap<String, Lexicon>,
+    synsets: HashMap<String, Synset>,
+    lexical_entries: HashMap<String, LexicalEntry>,
+    senses: HashMap<String, Sense>,
+
+    synset_relation_count: usize,
+    sense_relation_count: usize,
+
+    found_lexical_resource: bool,
+    lexicon_count: usize,
+    current_lexicon: Option<String>,
+    entry_count: usize,
+    synset_count: usize,
+
+    current_entry: Option<LexicalEntry>,
+    lemma_seen: bool,
+    current_sense: Option<Sense>,
+
+    current_synset: Option<Synset>,
+    definition_seen: bool,
+    ili_definition_seen: bool,
+    ili_definition: Option<String>,
+
+    text_target: Option<TextTarget>,
+    text: String,
+}
+
+pub fn parse_wordnet_reader<R: BufRead>(source: R) -> Result<WordNetData> {
+    let start_time = Instant::now();
 
-    let mut synset_relation_count = 0;
-    let mut sense_relation_count = 0;
+    let mut reader = Reader::from_reader(source);
+    reader.config_mut().expand_empty_elements = true;
+
+    let mut state = ParseState::default();
+    // Element names from the root down to the current element. Its depth is
+    // bounded by the LMF schema, so memory stays flat regardless of input size.
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
 
     println!("Processing lexicons...");
     let pb = indicatif::ProgressBar::new_spinner();
     pb.set_message("Processing lexicons...");
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    if let Some(lr) = lexical_resource {
-        for lexicon in lr
-            .children()
-            .filter(|n| n.is_element() && n.tag_name().name() == "Lexicon")
-        {
-            let lexicon_id = lexicon.attribute("id").unwrap_or("unknown");
-            let language = lexicon.attribute("language").unwrap_or("unknown");
-            let label = lexicon.attribute("label").unwrap_or("unknown");
-
-            println!(
-                "Processing lexicon: {} ({}) - Language: {}",
-                lexicon_id, label, language
-            );
-
-            let mut entry_count = 0;
-            for entry_node in lexicon
-                .children()
-                .filter(|n| n.is_element() && n.tag_name().name() == "LexicalEntry")
-            {
-                process_lexical_entry(
-                    entry_node,
-                    &mut lexical_entries,
-                    &mut senses,
-                    &mut sense_relation_count,
-                )?;
-
-                entry_count += 1;
-                if entry_count % 5000 == 0 {
-                    pb.set_message(format!("Processed {} lexical entries...", entry_count));
-                }
+    loop {
+        let event = reader.read_event_into(&mut buf).context(format!(
+            "Failed to parse XML at position {}",
+            reader.buffer_position()
+        ))?;
+
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let parent = path.last().map(String::as_str);
+                handle_start(&mut state, &e, &name, parent)?;
+                path.push(name);
             }
-
-            let mut synset_count = 0;
-            for synset_node in lexicon
-                .children()
-                .filter(|n| n.is_element() && n.tag_name().name() == "Synset")
-            {
-                process_synset(synset_node, &mut synsets, &mut synset_relation_count)?;
-
-                synset_count += 1;
-                if synset_count % 10000 == 0 {
-                    pb.set_message(format!("Processed {} synsets...", synset_count));
-                }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str);
+                handle_end(&mut state, &name, parent, &pb);
             }
-
-            println!(
-                "Processed {} lexical entries and {} synsets in lexicon '{}'",
-                entry_count, synset_count, lexicon_id
-            );
+            Event::Text(e) if state.text_target.is_some() => {
+                state
+                    .text
+                    .push_str(&e.unescape().context("Failed to unescape XML text")?);
+            }
+            Event::CData(e) if state.text_target.is_some() => {
+                state.text.push_str(&String::from_utf8_lossy(&e));
+            }
+            Event::Eof => break,
+            _ => {}
         }
-    } else {
+
+        buf.clear();
+    }
+
+    if !state.found_lexical_resource {
         println!(
             "Warning: No LexicalResource element found. XML might not match expected structure."
         );
+    } else if state.lexicon_count == 0 {
+        println!("No Lexicon elements found under LexicalResource");
+    } else {
+        println!("Found {} Lexicon elements", state.lexicon_count);
     }
 
     pb.finish_with_message(format!(
         "Parsing complete: {} lexical entries, {} senses, {} synsets, {} relations",
-        lexical_entries.len(),
-        senses.len(),
-        synsets.len(),
-        synset_relation_count + sense_relation_count
+        state.lexical_entries.len(),
+        state.senses.len(),
+        state.synsets.len(),
+        state.synset_relation_count + state.sense_relation_count
     ));
 
     println!(
         "Synset relations: {}, Sense relations: {}",
-        synset_relation_count, sense_relation_count
+        state.synset_relation_count, state.sense_relation_count
     );
 
-    if synset_relation_count > 0 {
+    if state.synset_relation_count > 0 {
         println!("Sample synset relations:");
-        let mut count = 0;
-        for synset in synsets.values() {
-            for rel in &synset.relations {
-                if count < 5 {
-                    println!(
-                        "  Synset {} -> {} -> {}",
-                        synset.id, rel.rel_type, rel.target
-                    );
-                    count += 1;
-                } else {
-                    break;
-                }
-            }
-            if count >= 5 {
-                break;
-            }
+        for (synset, rel) in state
+            .synsets
+            .values()
+            .flat_map(|s| s.relations.iter().map(move |r| (s, r)))
+            .take(5)
+        {
+            println!(
+                "  Synset {} -> {} -> {}",
+                synset.id, rel.rel_type, rel.target
+            );
         }
     }
 
-    if sense_relation_count > 0 {
+    if state.sense_relation_count > 0 {
         println!("Sample sense relations:");
-        let mut count = 0;
-        for sense in senses.values() {
-            for rel in &sense.relations {
-                if count < 5 {
-                    println!("  Sense {} -> {} -> {}", sense.id, rel.rel_type, rel.target);
-                    count += 1;
-                } else {
-                    break;
-                }
-            }
-            if count >= 5 {
-                break;
-            }
+        for (sense, rel) in state
+            .senses
+            .values()
+            .flat_map(|s| s.relations.iter().map(move |r| (s, r)))
+            .take(5)
+        {
+            println!("  Sense {} -> {} -> {}", sense.id, rel.rel_type, rel.target);
         }
     }
 
     println!("Total parsing time: {:.2?}", start_time.elapsed());
 
     Ok(WordNetData {
-        synsets,
-        lexical_entries,
-        senses,
+        lexicons: state.lexicons,
+        synsets: state.synsets,
+        lexical_entries: state.lexical_entries,
+        senses: state.senses,
     })
 }
 
-fn process_lexical_entry(
-    node: Node,
-    lexical_entries: &mut HashMap<String, LexicalEntry>,
-    senses: &mut HashMap<String, Sense>,
-    sense_relation_count: &mut usize,
+fn handle_start(
+    state: &mut ParseState,
+    e: &BytesStart,
+    name: &str,
+    parent: Option<&str>,
 ) -> Result<()> {
-    let id = node.attribute("id").context("LexicalEntry missing id")?;
-
-    let mut entry = LexicalEntry {
-        id: id.to_string(),
-        lemma: Lemma {
-            written_form: String::new(),
-            part_of_speech: String::new(),
-        },
-        senses: Vec::new(),
-    };
-
-    if let Some(lemma_node) = node
-        .children()
-        .find(|n| n.is_element() && n.tag_name().name() == "Lemma")
-    {
-        let written_form = lemma_node
-            .attribute("writtenForm")
-            .unwrap_or("")
-            .to_string();
-        let pos = lemma_node
-            .attribute("partOfSpeech")
-            .unwrap_or("")
-            .to_string();
-
-        entry.lemma.written_form = written_form;
-        entry.lemma.part_of_speech = pos;
-    }
+    match (parent, name) {
+        (None, "LexicalResource") => {
+            state.found_lexical_resource = true;
+            println!("Found LexicalResource element");
+        }
+        (Some("LexicalResource"), "Lexicon") => {
+            let lexicon_id = attribute(e, "id")?.unwrap_or_else(|| "unknown".to_string());
+            let language = attribute(e, "language")?.unwrap_or_else(|| "unknown".to_string());
+            let label = attribute(e, "label")?.unwrap_or_else(|| "unknown".to_string());
 
-    for sense_node in node
-        .children()
-        .filter(|n| n.is_element() && n.tag_name().name() == "Sense")
-    {
-        let sense_id = sense_node.attribute("id").context("Sense missing id")?;
-        let synset_id = sense_node
-            .attribute("synset")
-            .context("Sense missing synset")?;
-
-        let mut sense = Sense {
-            id: sense_id.to_string(),
-            synset_id: synset_id.to_string(),
-            relations: Vec::new(),
-            examples: Vec::new(),
-        };
-
-        entry.senses.push(sense_id.to_string());
-
-        for relation_node in sense_node
-            .children()
-            .filter(|n| n.is_element() && n.tag_name().name() == "SenseRelation")
-        {
-            let rel_type = relation_node
-                .attribute("relType")
-                .context("SenseRelation missing relType")?;
-            let target = relation_node
-                .attribute("target")
-                .context("SenseRelation missing target")?;
-
-            sense.relations.push(SenseRelation {
-                rel_type: rel_type.to_string(),
-                target: target.to_string(),
+            println!(
+                "Processing lexicon: {} ({}) - Language: {}",
+                lexicon_id, label, language
+            );
+
+            state.lexicon_count += 1;
+            state.lexicons.insert(
+                lexicon_id.clone(),
+                Lexicon {
+                    id: lexicon_id.clone(),
+                    label,
+                    language,
+                },
+            );
+            state.current_lexicon = Some(lexicon_id);
+            state.entry_count = 0;
+            state.synset_count = 0;
+        }
+        (Some("Lexicon"), "LexicalEntry") => {
+            let id = attribute(e, "id")?.context("LexicalEntry missing id")?;
+
+            state.current_entry = Some(LexicalEntry {
+                id,
+                lexicon: state.current_lexicon.clone().unwrap_or_default(),
+                lemma: Lemma {
+                    written_form: String::new(),
+                    part_of_speech: String::new(),
+                },
+                forms: Vec::new(),
+                senses: Vec::new(),
             });
+            state.lemma_seen = false;
+        }
+        (Some("LexicalEntry"), "Lemma") if !state.lemma_seen => {
+            state.lemma_seen = true;
+            if let Some(entry) = state.current_entry.as_mut() {
+                entry.lemma.written_form = attribute(e, "writtenForm")?.unwrap_or_default();
+                entry.lemma.part_of_speech = attribute(e, "partOfSpeech")?.unwrap_or_default();
+            }
+        }
+        (Some("LexicalEntry"), "Form") => {
+            if let Some(form) = attribute(e, "writtenForm")?
+                && !form.trim().is_empty()
+                && let Some(entry) = state.current_entry.as_mut()
+            {
+                entry.forms.push(form);
+            }
+        }
+        (Some("LexicalEntry"), "Sense") => {
+            let sense_id = attribute(e, "id")?.context("Sense missing id")?;
+            let synset_id = attribute(e, "synset")?.context("Sense missing synset")?;
 
-            *sense_relation_count += 1;
+            if let Some(entry) = state.current_entry.as_mut() {
+                entry.senses.push(sense_id.clone());
+            }
+
+            state.current_sense = Some(Sense {
+                id: sense_id,
+                synset_id,
+                relations: Vec::new(),
+                examples: Vec::new(),
+            });
         }
+        (Some("Sense"), "SenseRelation") => {
+            let rel_type = attribute(e, "relType")?.context("SenseRelation missing relType")?;
+            let target = attribute(e, "target")?.context("SenseRelation missing target")?;
 
-        for example_node in sense_node.children().filter(|n| {
-            (n.is_element() && n.tag_name().name() == "SenseExample")
-                || (n.is_element() && n.tag_name().name() == "Example")
-        }) {
-            if let Some(text) = example_node.text() {
-                sense.examples.push(text.trim().to_string());
+            if let Some(sense) = state.current_sense.as_mut() {
+                sense.relations.push(SenseRelation { rel_type, target });
+                state.sense_relation_count += 1;
             }
         }
+        (Some("Sense"), "SenseExample" | "Example") => {
+            start_text(state, TextTarget::SenseExample);
+        }
+        (Some("Lexicon"), "Synset") => {
+            let id = attribute(e, "id")?.context("Synset missing id")?;
+            let ili = attribute(e, "ili")?;
+            let part_of_speech = attribute(e, "partOfSpeech")?.unwrap_or_default();
+            let members = attribute(e, "members")?
+                .map(|s| s.split_whitespace().map(|id| id.to_string()).collect())
+                .unwrap_or_default();
+
+            state.current_synset = Some(Synset {
+                id,
+                lexicon: state.current_lexicon.clone().unwrap_or_default(),
+                ili,
+                part_of_speech,
+                definition: None,
+                examples: Vec::new(),
+                relations: Vec::new(),
+                members,
+            });
+            state.definition_seen = false;
+            state.ili_definition_seen = false;
+            state.ili_definition = None;
+        }
+        (Some("Synset"), "Definition") if !state.definition_seen => {
+            state.definition_seen = true;
+            start_text(state, TextTarget::SynsetDefinition);
+        }
+        (Some("Synset"), "ILIDefinition") if !state.ili_definition_seen => {
+            state.ili_definition_seen = true;
+            start_text(state, TextTarget::SynsetIliDefinition);
+        }
+        (Some("Synset"), "Example") => {
+            start_text(state, TextTarget::SynsetExample);
+        }
+        (Some("Synset"), "SynsetRelation") => {
+            let rel_type = attribute(e, "relType")?.context("SynsetRelation missing relType")?;
+            let target = attribute(e, "target")?.context("SynsetRelation missing target")?;
 
-        senses.insert(sense_id.to_string(), sense);
+            if let Some(synset) = state.current_synset.as_mut() {
+                synset.relations.push(SynsetRelation { rel_type, target });
+                state.synset_relation_count += 1;
+            }
+        }
+        _ => {}
     }
 
-    if !entry.lemma.written_form.trim().is_empty() {
-        lexical_entries.insert(id.to_string(), entry);
+    Ok(())
+}
+
+fn handle_end(
+    state: &mut ParseState,
+    name: &str,
+    parent: Option<&str>,
+    pb: &indicatif::ProgressBar,
+) {
+    match (parent, name) {
+        (Some("LexicalResource"), "Lexicon") => {
+            println!(
+                "Processed {} lexical entries and {} synsets in lexicon '{}'",
+                state.entry_count,
+                state.synset_count,
+                state.current_lexicon.take().unwrap_or_default()
+            );
+        }
+        (Some("Lexicon"), "LexicalEntry") => {
+            if let Some(entry) = state.current_entry.take()
+                && !entry.lemma.written_form.trim().is_empty()
+            {
+                state.lexical_entries.insert(entry.id.clone(), entry);
+            }
+
+            state.entry_count += 1;
+            if state.entry_count.is_multiple_of(5000) {
+                pb.set_message(format!(
+                    "Processed {} lexical entries...",
+                    state.entry_count
+                ));
+            }
+        }
+        (Some("LexicalEntry"), "Sense") => {
+            if let Some(sense) = state.current_sense.take() {
+                state.senses.insert(sense.id.clone(), sense);
+            }
+        }
+        (Some("Sense"), "SenseExample" | "Example") => {
+            if let Some(text) = finish_text(state, TextTarget::SenseExample)
+                && let Some(sense) = state.current_sense.as_mut()
+            {
+                sense.examples.push(text);
+            }
+        }
+        (Some("Lexicon"), "Synset") => {
+            if let Some(mut synset) = state.current_synset.take() {
+                if synset.definition.is_none() {
+                    synset.definition = state.ili_definition.take();
+                }
+                state.synsets.insert(synset.id.clone(), synset);
+            }
+
+            state.synset_count += 1;
+            if state.synset_count.is_multiple_of(10000) {
+                pb.set_message(format!("Processed {} synsets...", state.synset_count));
+            }
+        }
+        (Some("Synset"), "Definition") => {
+            if let Some(text) = finish_text(state, TextTarget::SynsetDefinition)
+                && let Some(synset) = state.current_synset.as_mut()
+            {
+                synset.definition = Some(text);
+            }
+        }
+        (Some("Synset"), "ILIDefinition") => {
+            if let Some(text) = finish_text(state, TextTarget::SynsetIliDefinition) {
+                state.ili_definition = Some(text);
+            }
+        }
+        (Some("Synset"), "Example") => {
+            if let Some(text) = finish_text(state, TextTarget::SynsetExample)
+                && let Some(synset) = state.current_synset.as_mut()
+            {
+                synset.examples.push(text);
+            }
+        }
+        _ => {}
     }
+}
 
-    Ok(())
+fn start_text(state: &mut ParseState, target: TextTarget) {
+    state.text_target = Some(target);
+    state.text.clear();
 }
 
-fn process_synset(
-    node: Node,
-    synsets: &mut HashMap<String, Synset>,
-    synset_relation_count: &mut usize,
-) -> Result<()> {
-    let id = node.attribute("id").context("Synset missing id")?;
-
-    let ili = node.attribute("ili").map(|s| s.to_string());
-    let pos = node.attribute("partOfSpeech").unwrap_or("").to_string();
-
-    let members = node
-        .attribute("members")
-        .map(|s| s.split_whitespace().map(|id| id.to_string()).collect())
-        .unwrap_or_default();
-
-    let mut synset = Synset {
-        id: id.to_string(),
-        ili,
-        part_of_speech: pos,
-        definition: None,
-        examples: Vec::new(),
-        relations: Vec::new(),
-        members,
-    };
-
-    if let Some(def_node) = node
-        .children()
-        .find(|n| n.is_element() && n.tag_name().name() == "Definition")
-    {
-        if let Some(text) = def_node.text() {
-            synset.definition = Some(text.trim().to_string());
+/// Returns the trimmed text collected for `target`, or `None` when the
+/// element had no character data at all.
+fn finish_text(state: &mut ParseState, target: TextTarget) -> Option<String> {
+    if state.text_target != Some(target) {
+        return None;
+    }
+
+    state.text_target = None;
+    if state.text.is_empty() {
+        return None;
+    }
+
+    Some(state.text.trim().to_string())
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attr in e.attributes() {
+        let attr = attr.context("Malformed XML attribute")?;
+        if attr.key.as_ref() == name.as_bytes() {
+            let value = attr
+                .unescape_value()
+                .context(format!("Failed to unescape attribute {}", name))?;
+            return Ok(Some(value.into_owned()));
         }
     }
 
-    if synset.definition.is_none() {
-        if let Some(ili_def_node) = node
-            .children()
-            .find(|n| n.is_element() && n.tag_name().name() == "ILIDefinition")
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wordnet-sample.xml"
+    );
+
+    /// What the DOM parser that the streaming parser replaced (the baseline
+    /// `parse_wordnet_xml`) produced for `FIXTURE`.
+    const BASELINE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wordnet-sample.baseline.json"
+    );
+
+    /// `data` as JSON, without the fields the baseline parser did not have.
+    fn without_new_fields(data: &WordNetData) -> serde_json::Value {
+        let mut value = serde_json::to_value(data).unwrap();
+        let object = value.as_object_mut().unwrap();
+
+        object.remove("lexicons");
+        for entry in object["lexical_entries"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
         {
-            if let Some(text) = ili_def_node.text() {
-                synset.definition = Some(text.trim().to_string());
-            }
+            let entry = entry.as_object_mut().unwrap();
+            entry.remove("lexicon");
+            entry.remove("forms");
         }
+        for synset in object["synsets"].as_object_mut().unwrap().values_mut() {
+            synset.as_object_mut().unwrap().remove("lexicon");
+        }
+
+        value
     }
 
-    for example_node in node
-        .children()
-        .filter(|n| n.is_element() && n.tag_name().name() == "Example")
-    {
-        if let Some(text) = example_node.text() {
-            synset.examples.push(text.trim().to_string());
-        }
+    #[test]
+    fn streaming_parser_matches_baseline_parser() {
+        let expected: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(BASELINE).unwrap()).unwrap();
+        let actual = parse_wordnet_file(Path::new(FIXTURE)).unwrap();
+
+        assert_eq!(without_new_fields(&actual), expected);
+        assert_eq!(actual.lexical_entries.len(), 5);
+        assert_eq!(actual.senses.len(), 7);
+        assert_eq!(actual.synsets.len(), 6);
+        assert_eq!(
+            actual.synsets["oewn-01945000-v"].definition.as_deref(),
+            Some("tip laterally")
+        );
+        assert_eq!(
+            actual.senses["oewn-bank__1.14.00.."].examples,
+            vec!["the bank & its customers".to_string()]
+        );
+        assert_eq!(
+            actual.lexical_entries["oewn-bank-n"].forms,
+            vec!["banks".to_string()]
+        );
     }
 
-    for relation_node in node
-        .children()
-        .filter(|n| n.is_element() && n.tag_name().name() == "SynsetRelation")
-    {
-        let rel_type = relation_node
-            .attribute("relType")
-            .context("SynsetRelation missing relType")?;
-        let target = relation_node
-            .attribute("target")
-            .context("SynsetRelation missing target")?;
-
-        synset.relations.push(SynsetRelation {
-            rel_type: rel_type.to_string(),
-            target: target.to_string(),
-        });
-
-        *synset_relation_count += 1;
+    #[test]
+    fn keeps_lexicon_and_language() {
+        let data = parse_wordnet_file(Path::new(FIXTURE)).unwrap();
+
+        assert_eq!(data.lexicons.len(), 2);
+        assert_eq!(data.language_of("oewn"), "en");
+        assert_eq!(data.language_of("omw-fr"), "fr");
+        assert_eq!(data.lexicons["omw-fr"].label, "WOLF");
+        assert_eq!(data.lexical_entries["omw-fr-banque-n"].lexicon, "omw-fr");
+        assert_eq!(data.synsets["oewn-08437235-n"].lexicon, "oewn");
     }
 
-    synsets.insert(id.to_string(), synset);
+    #[test]
+    fn reads_gzip_input_directly() {
+        let xml = std::fs::read(FIXTURE).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let gz_path = temp_dir.path().join("wordnet-sample.xml.gz");
 
-    Ok(())
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&xml).unwrap();
+        encoder.finish().unwrap();
+
+        let from_gzip = parse_wordnet_file(&gz_path).unwrap();
+        let from_plain = parse_wordnet_file(Path::new(FIXTURE)).unwrap();
+
+        assert_eq!(from_gzip, from_plain);
+    }
 }
@@ -0,0 +1,397 @@
+use anyhow::{Context, Result};
+use meilisearch_sdk::client::{Client, SwapIndexes};
+use meilisearch_sdk::documents::DocumentsQuery;
+use meilisearch_sdk::indexes::Index;
+use meilisearch_sdk::search::SearchResults;
+use meilisearch_sdk::tasks::TasksSearchQuery;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+const INDEXING_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+const SAMPLE_LOOKUPS: usize = 5;
+
+/// Bookkeeping index listing the indexes that were swapped out of service.
+const HISTORY_INDEX: &str = "swap_history";
+const HISTORY_PAGE_SIZE: usize = 1000;
+
+/// An index that held live data until a swap replaced it. Only these are
+/// rolled back to or pruned, never a staging build that was not swapped in.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviousIndex {
+    /// Uid of the index now holding the old data
+    pub id: String,
+    pub live: String,
+    pub swapped_at: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Name of a fresh staging index for `live`, e.g. `words_1760000000`.
+pub fn staging_index_name(live: &str) -> String {
+    format!("{}_{}", live, unix_now())
+}
+
+/// Creates an empty index with `id` as its primary key.
+pub async fn create_index(client: &Client, uid: &str) -> Result<()> {
+    println!("Creating index '{}'", uid);
+
+    let task = client
+        .create_index(uid, Some("id"))
+        .await?
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await?;
+
+    if task.is_failure() {
+        anyhow::bail!(
+            "Failed to create index '{}': {}",
+            uid,
+            task.unwrap_failure()
+        );
+    }
+
+    Ok(())
+}
+
+/// Blocks until the index has no enqueued or processing tasks left, giving
+/// up after [`INDEXING_TIMEOUT`].
+pub async fn wait_for_index_idle(client: &Client, uid: &str) -> Result<()> {
+    println!("Waiting for indexing of '{}' to finish...", uid);
+
+    let deadline = Instant::now() + INDEXING_TIMEOUT;
+
+    loop {
+        let mut query = TasksSearchQuery::new(client);
+        query
+            .with_index_uids([uid])
+            .with_statuses(["enqueued", "processing"])
+            .with_limit(1);
+
+        let pending = client.get_tasks_with(&query).await?;
+
+        if pending.results.is_empty() {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "Indexing of '{}' did not finish within {:?}",
+                uid,
+                INDEXING_TIMEOUT
+            );
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Checks that the staging index holds every document and that a few
+/// lemmas can be looked up the way `thesaurus-api` does it.
+pub async fn sanity_check(
+    client: &Client,
+    uid: &str,
+    expected_documents: usize,
+    sample_words: &[String],
+) -> Result<()> {
+    println!("Running sanity checks on '{}'", uid);
+
+    let mut query = TasksSearchQuery::new(client);
+    query
+        .with_index_uids([uid])
+        .with_statuses(["failed"])
+        .with_limit(1);
+
+    let failed = client.get_tasks_with(&query).await?;
+
+    if !failed.results.is_empty() {
+        anyhow::bail!("Staging index '{}' has failed indexing tasks", uid);
+    }
+
+    let index = client.index(uid);
+    let stats = index.get_stats().await?;
+
+    if stats.number_of_documents != expected_documents {
+        anyhow::bail!(
+            "Staging index '{}' has {} documents, expected {}",
+            uid,
+            stats.number_of_documents,
+            expected_documents
+        );
+    }
+
+    println!("  Document count OK ({})", stats.number_of_documents);
+
+    for word in sample_words {
+        let filter = format!("word = {}", quote_filter_value(word));
+        let results: SearchResults<serde_json::Value> = index
+            .search()
+            .with_filter(&filter)
+            .with_limit(1)
+            .execute()
+            .await?;
+
+        if results.hits.is_empty() {
+            anyhow::bail!("Sample lookup for '{}' in '{}' returned nothing", word, uid);
+        }
+
+        println!("  Lookup OK: {}", word);
+    }
+
+    Ok(())
+}
+
+/// Quotes `value` for a Meilisearch filter, escaping backslashes before
+/// quotes. Kept in step with `quote_filter_value` in `thesaurus-api`.
+fn quote_filter_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Picks a handful of words spread evenly across `words` for lookups.
+pub fn sample_words<'a>(words: impl ExactSizeIterator<Item = &'a str>) -> Vec<String> {
+    let total = words.len();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let step = (total / SAMPLE_LOOKUPS).max(1);
+    words
+        .step_by(step)
+        .take(SAMPLE_LOOKUPS)
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Atomically swaps every `(live, staging)` pair in a single task. Returns
+/// the staging indexes that now hold what was live, leaving out live
+/// indexes that had to be created empty first.
+pub async fn swap_indexes(client: &Client, pairs: &[(String, String)]) -> Result<Vec<String>> {
+    let mut replaced = Vec::new();
+
+    for (live, staging) in pairs {
+        if client.get_index(live).await.is_err() {
+            println!("Live index '{}' does not exist yet, creating it", live);
+            create_index(client, live).await?;
+        } else {
+            replaced.push(staging.clone());
+        }
+    }
+
+    let swaps: Vec<SwapIndexes> = pairs
+        .iter()
+        .map(|(live, staging)| {
+            println!("Swapping '{}' <-> '{}'", live, staging);
+            SwapIndexes {
+                indexes: (live.clone(), staging.clone()),
+            }
+        })
+        .collect();
+
+    let task = client
+        .swap_indexes(&swaps)
+        .await?
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await?;
+
+    if task.is_failure() {
+        anyhow::bail!("Index swap failed: {}", task.unwrap_failure());
+    }
+
+    println!("Index swap complete");
+
+    Ok(replaced)
+}
+
+/// Swaps verified staging indexes in and records the indexes they replaced
+/// in [`HISTORY_INDEX`], so they can be rolled back to. Staging indexes
+/// that received a live index created empty for the swap are deleted.
+pub async fn swap_in(client: &Client, pairs: &[(String, String)]) -> Result<()> {
+    let replaced = swap_indexes(client, pairs).await?;
+    let swapped_at = unix_now();
+
+    for (_, staging) in pairs {
+        if !replaced.contains(staging) {
+            println!("Deleting empty index '{}'", staging);
+            client.delete_index(staging).await?;
+        }
+    }
+
+    let records: Vec<PreviousIndex> = pairs
+        .iter()
+        .filter(|(_, staging)| replaced.contains(staging))
+        .map(|(live, staging)| PreviousIndex {
+            id: staging.clone(),
+            live: live.clone(),
+            swapped_at,
+        })
+        .collect();
+
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let history = history_index(client).await?;
+    let task = history
+        .add_or_replace(&records, Some("id"))
+        .await?
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await?;
+
+    if task.is_failure() {
+        anyhow::bail!(
+            "Failed to record the swapped out indexes: {}",
+            task.unwrap_failure()
+        );
+    }
+
+    Ok(())
+}
+
+/// Deletes staging indexes that were never swapped in, e.g. after a failed
+/// build. Problems are only reported, so the build error is what surfaces.
+pub async fn discard_staging_indexes(client: &Client, uids: &[&str]) {
+    for uid in uids {
+        if client.get_index(*uid).await.is_err() {
+            continue;
+        }
+
+        println!("Deleting unused staging index '{}'", uid);
+        if let Err(e) = client.delete_index(*uid).await {
+            println!("  Failed to delete '{}': {}", uid, e);
+        }
+    }
+}
+
+async fn history_index(client: &Client) -> Result<Index> {
+    match client.get_index(HISTORY_INDEX).await {
+        Ok(index) => Ok(index),
+        Err(_) => {
+            create_index(client, HISTORY_INDEX).await?;
+            Ok(client.index(HISTORY_INDEX))
+        }
+    }
+}
+
+/// Indexes that were swapped out of `live`, most recent first.
+pub async fn previous_indexes(client: &Client, live: &str) -> Result<Vec<PreviousIndex>> {
+    let Ok(history) = client.get_index(HISTORY_INDEX).await else {
+        return Ok(Vec::new());
+    };
+
+    let mut records: Vec<PreviousIndex> = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let page = DocumentsQuery::new(&history)
+            .with_offset(offset)
+            .with_limit(HISTORY_PAGE_SIZE)
+            .execute::<PreviousIndex>()
+            .await
+            .context("Failed to read the swap history")?;
+
+        let fetched = page.results.len();
+        records.extend(
+            page.results
+                .into_iter()
+                .filter(|record| record.live == live),
+        );
+
+        offset += fetched;
+        if fetched < HISTORY_PAGE_SIZE || offset >= page.total as usize {
+            break;
+        }
+    }
+
+    records.sort_by(|a, b| {
+        b.swapped_at
+            .cmp(&a.swapped_at)
+            .then_with(|| b.id.cmp(&a.id))
+    });
+
+    Ok(records)
+}
+
+/// Deletes previous indexes along with their history records.
+async fn delete_previous_indexes(client: &Client, uids: &[String]) -> Result<()> {
+    if uids.is_empty() {
+        return Ok(());
+    }
+
+    for uid in uids {
+        println!("Deleting old index '{}'", uid);
+        if client.get_index(uid).await.is_ok() {
+            client.delete_index(uid).await?;
+        }
+    }
+
+    let task = client
+        .index(HISTORY_INDEX)
+        .delete_documents(uids)
+        .await?
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await?;
+
+    if task.is_failure() {
+        anyhow::bail!(
+            "Failed to update the swap history: {}",
+            task.unwrap_failure()
+        );
+    }
+
+    Ok(())
+}
+
+/// Deletes all but the `keep` most recently swapped out indexes of `live`.
+pub async fn prune_previous_indexes(client: &Client, live: &str, keep: usize) -> Result<()> {
+    let stale: Vec<String> = previous_indexes(client, live)
+        .await?
+        .into_iter()
+        .skip(keep)
+        .map(|record| record.id)
+        .collect();
+
+    delete_previous_indexes(client, &stale).await
+}
+
+/// Swaps each live index back with the index it most recently replaced.
+/// The rolled back data ends up in those indexes, which are then deleted.
+pub async fn rollback(client: &Client, lives: &[&str]) -> Result<()> {
+    let mut pairs = Vec::new();
+
+    for live in lives {
+        let previous = previous_indexes(client, live)
+            .await?
+            .into_iter()
+            .next()
+            .context(format!(
+                "No previous index found to roll '{}' back to",
+                live
+            ))?;
+
+        pairs.push((live.to_string(), previous.id));
+    }
+
+    swap_indexes(client, &pairs).await?;
+
+    let rolled_back: Vec<String> = pairs.into_iter().map(|(_, uid)| uid).collect();
+
+    delete_previous_indexes(client, &rolled_back).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_filter_value_escapes_quotes_and_backslashes() {
+        assert_eq!(quote_filter_value("bank"), "\"bank\"");
+        assert_eq!(quote_filter_value("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_filter_value("a\\\"b"), "\"a\\\\\\\"b\"");
+        assert_eq!(quote_filter_value("a\\"), "\"a\\\\\"");
+    }
+}
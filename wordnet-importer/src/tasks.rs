@@ -0,0 +1,286 @@
+use anyhow::Result;
+use indicatif::{ProgressBar, ProgressStyle};
+use meilisearch_sdk::client::Client;
+use meilisearch_sdk::errors::Error;
+use meilisearch_sdk::task_info::TaskInfo;
+use std::future::Future;
+use std::time::Duration;
+
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A batch whose documents never made it into the index.
+#[derive(Debug)]
+pub struct BatchFailure {
+    pub batch: usize,
+    pub documents: usize,
+    pub attempts: u32,
+    pub error: String,
+}
+
+/// What to do once attempt number `attempt` (counting from 1) has failed.
+#[derive(Debug, PartialEq, Eq)]
+enum Retry {
+    After(Duration),
+    GiveUp,
+}
+
+/// The retry policy: up to [`MAX_ATTEMPTS`] attempts, waiting twice as long
+/// after each failure, capped at [`MAX_BACKOFF`].
+fn next_retry(attempt: u32) -> Retry {
+    if attempt >= MAX_ATTEMPTS {
+        return Retry::GiveUp;
+    }
+
+    Retry::After(backoff(attempt))
+}
+
+fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Waits for an enqueued task to finish, returning the Meilisearch error
+/// message if it failed or never completed.
+pub async fn wait_for_task(client: &Client, task: TaskInfo) -> Result<(), String> {
+    let task_uid = task.task_uid;
+
+    match task
+        .wait_for_completion(client, Some(POLL_INTERVAL), Some(TASK_TIMEOUT))
+        .await
+    {
+        Ok(task) if task.is_failure() => Err(format!(
+            "task {} failed: {}",
+            task_uid,
+            task.unwrap_failure()
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("task {} did not complete: {}", task_uid, e)),
+    }
+}
+
+/// Runs a settings update and fails unless its task succeeds.
+pub async fn apply_setting<Fut>(client: &Client, name: &str, update: Fut) -> Result<()>
+where
+    Fut: Future<Output = Result<TaskInfo, Error>>,
+{
+    let task = update.await?;
+
+    wait_for_task(client, task)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to apply {}: {}", name, e))
+}
+
+async fn enqueue_with_retry<F, Fut>(batch_num: usize, enqueue: &F) -> Result<TaskInfo, String>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<TaskInfo, Error>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        let e = match enqueue().await {
+            Ok(task) => return Ok(task),
+            Err(e) => e,
+        };
+
+        let Retry::After(delay) = next_retry(attempt) else {
+            return Err(e.to_string());
+        };
+
+        println!(
+            "Warning: Failed to enqueue batch {} (attempt {}): {}. Retrying in {:.1?}",
+            batch_num, attempt, e, delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Enqueues `items` in batches, then follows every task to completion and
+/// re-enqueues failed batches with exponential backoff. Returns the batches
+/// that still failed after all attempts.
+pub async fn run_batches<'a, T, F, Fut>(
+    client: &Client,
+    label: &str,
+    items: &'a [T],
+    batch_size: usize,
+    enqueue: F,
+) -> Vec<BatchFailure>
+where
+    F: Fn(&'a [T]) -> Fut,
+    Fut: Future<Output = Result<TaskInfo, Error>>,
+{
+    let total_batches = items.len().div_ceil(batch_size);
+    let mut failures = Vec::new();
+    let mut pending = Vec::with_capacity(total_batches);
+
+    let pb = ProgressBar::new((total_batches * 2) as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(&format!(
+                "{{spinner:.green}} [{{elapsed_precise}}] [{{bar:40.cyan/blue}}] {{pos}}/{{len}} {} steps ({{eta}})",
+                label
+            ))
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    // Enqueue everything first so Meilisearch can index while we keep sending.
+    for (i, chunk) in items.chunks(batch_size).enumerate() {
+        let batch_num = i + 1;
+
+        match enqueue_with_retry(batch_num, &|| enqueue(chunk)).await {
+            Ok(task) => pending.push((batch_num, chunk, task)),
+            Err(error) => {
+                failures.push(BatchFailure {
+                    batch: batch_num,
+                    documents: chunk.len(),
+                    attempts: MAX_ATTEMPTS,
+                    error,
+                });
+                pb.inc(1);
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        pb.inc(1);
+    }
+
+    for (batch_num, chunk, task) in pending {
+        let mut task = task;
+        let mut attempt = 1;
+
+        loop {
+            let error = match wait_for_task(client, task).await {
+                Ok(()) => break,
+                Err(error) => error,
+            };
+
+            let Retry::After(delay) = next_retry(attempt) else {
+                failures.push(BatchFailure {
+                    batch: batch_num,
+                    documents: chunk.len(),
+                    attempts: attempt,
+                    error,
+                });
+                break;
+            };
+
+            println!(
+                "Warning: Batch {} failed (attempt {}): {}. Retrying in {:.1?}",
+                batch_num, attempt, error, delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+
+            match enqueue_with_retry(batch_num, &|| enqueue(chunk)).await {
+                Ok(retried) => task = retried,
+                Err(error) => {
+                    failures.push(BatchFailure {
+                        batch: batch_num,
+                        documents: chunk.len(),
+                        attempts: attempt,
+                        error,
+                    });
+                    break;
+                }
+            }
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_with_message(format!("{} complete", label));
+
+    failures.sort_by_key(|f| f.batch);
+    failures
+}
+
+/// Prints a per-batch report and fails if any batch was not indexed.
+pub fn ensure_no_failures(
+    index_name: &str,
+    label: &str,
+    total_batches: usize,
+    failures: &[BatchFailure],
+) -> Result<()> {
+    if failures.is_empty() {
+        println!(
+            "All {} {} batches for '{}' succeeded",
+            total_batches, label, index_name
+        );
+        return Ok(());
+    }
+
+    let documents: usize = failures.iter().map(|f| f.documents).sum();
+
+    println!(
+        "\n{} of {} {} batches for '{}' failed ({} documents affected):",
+        failures.len(),
+        total_batches,
+        label,
+        index_name,
+        documents
+    );
+    for failure in failures {
+        println!(
+            "  Batch {}: {} documents, {} attempts, {}",
+            failure.batch, failure.documents, failure.attempts, failure.error
+        );
+    }
+
+    anyhow::bail!(
+        "{} {} batches for '{}' failed; {} documents were not processed",
+        failures.len(),
+        label,
+        index_name,
+        documents
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_retry_doubles_the_delay() {
+        assert_eq!(next_retry(1), Retry::After(Duration::from_millis(500)));
+        assert_eq!(next_retry(2), Retry::After(Duration::from_secs(1)));
+        assert_eq!(next_retry(3), Retry::After(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn next_retry_gives_up_after_the_last_attempt() {
+        assert_eq!(next_retry(MAX_ATTEMPTS), Retry::GiveUp);
+        assert_eq!(next_retry(MAX_ATTEMPTS + 1), Retry::GiveUp);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff(20), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn ensure_no_failures_fails_on_any_failed_batch() {
+        assert!(ensure_no_failures("words", "upload", 3, &[]).is_ok());
+
+        let failures = [BatchFailure {
+            batch: 2,
+            documents: 1000,
+            attempts: MAX_ATTEMPTS,
+            error: "task 7 failed".to_string(),
+        }];
+        let error = ensure_no_failures("words", "upload", 3, &failures).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("1000 documents were not processed")
+        );
+    }
+}
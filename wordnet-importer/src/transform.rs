@@ -1,9 +1,12 @@
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use crate::models::{MeiliWord, WordNetData};
+use crate::models::{
+    ConceptSynset, MeiliConcept, MeiliSense, MeiliSynset, MeiliWord, SynsetEdge, TypedRelation,
+    WordNetData,
+};
 
 pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
     let start_time = Instant::now();
@@ -73,72 +76,33 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
         println!("No sense relation types found!");
     }
 
-    println!("Building sense to lemma mapping...");
-    let mut sense_to_lemma: HashMap<String, String> = HashMap::new();
-
-    for (le_id, le) in &data.lexical_entries {
-        if le.lemma.written_form.trim().is_empty() {
-            continue;
-        }
-
-        for sense_id in &le.senses {
-            sense_to_lemma.insert(sense_id.clone(), le.lemma.written_form.clone());
-        }
-    }
-
-    println!("Created mapping for {} senses", sense_to_lemma.len());
-
-    println!("Building synset to lemmas mapping...");
-    let mut synset_to_lemmas: HashMap<String, Vec<String>> = HashMap::new();
-
-    for (sense_id, sense) in &data.senses {
-        if let Some(lemma) = sense_to_lemma.get(sense_id) {
-            if !lemma.trim().is_empty() {
-                synset_to_lemmas
-                    .entry(sense.synset_id.clone())
-                    .or_default()
-                    .push(lemma.clone());
-            }
-        }
-    }
-
-    for (synset_id, synset) in &data.synsets {
-        for member_id in &synset.members {
-            if let Some(lemma) = sense_to_lemma.get(member_id) {
-                if !lemma.trim().is_empty() {
-                    synset_to_lemmas
-                        .entry(synset_id.clone())
-                        .or_default()
-                        .push(lemma.clone());
-                }
-            }
-        }
-    }
-
-    for lemmas in synset_to_lemmas.values_mut() {
-        lemmas.sort();
-        lemmas.dedup();
-    }
-
-    println!(
-        "Created synset to lemmas mapping for {} synsets",
-        synset_to_lemmas.len()
-    );
+    let sense_to_lemma = build_sense_to_lemma(data);
+    let synset_to_lemmas = build_synset_to_lemmas(data, &sense_to_lemma);
 
     println!("Building lemma to synsets mapping...");
-    let mut lemma_to_synsets: HashMap<String, HashSet<String>> = HashMap::new();
+    // Lemmas are grouped per lexicon, so the same spelling in two languages
+    // (e.g. English and French "chat") becomes two separate words.
+    let mut lemma_to_synsets: HashMap<(String, String), HashSet<String>> = HashMap::new();
+    // Inflected forms such as "geese", which the API uses to find the lemma.
+    let mut lemma_forms: HashMap<(String, String), BTreeSet<String>> = HashMap::new();
 
-    for (le_id, le) in &data.lexical_entries {
+    for le in data.lexical_entries.values() {
         let lemma = le.lemma.written_form.clone();
 
         if lemma.trim().is_empty() || lemma.len() > 100 || !is_valid_lemma(&lemma) {
             continue;
         }
 
+        let forms = le.forms.iter().filter(|form| **form != lemma).cloned();
+        lemma_forms
+            .entry((le.lexicon.clone(), lemma.clone()))
+            .or_default()
+            .extend(forms);
+
         for sense_id in &le.senses {
             if let Some(sense) = data.senses.get(sense_id) {
                 lemma_to_synsets
-                    .entry(lemma.clone())
+                    .entry((le.lexicon.clone(), lemma.clone()))
                     .or_default()
                     .insert(sense.synset_id.clone());
             }
@@ -146,9 +110,14 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
     }
 
     for (synset_id, lemmas) in &synset_to_lemmas {
+        let lexicon = match data.synsets.get(synset_id) {
+            Some(synset) => &synset.lexicon,
+            None => continue,
+        };
+
         for lemma in lemmas {
             lemma_to_synsets
-                .entry(lemma.clone())
+                .entry((lexicon.clone(), lemma.clone()))
                 .or_default()
                 .insert(synset_id.clone());
         }
@@ -162,14 +131,16 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
     println!("Creating basic word entries...");
     let mut words: HashMap<String, MeiliWord> = HashMap::new();
 
-    for lemma in lemma_to_synsets.keys() {
-        let word_id = format!("word_{}", normalize_id(lemma));
+    for (lexicon, lemma) in lemma_to_synsets.keys() {
+        let word_id = word_id(lexicon, lemma);
 
         words.insert(
             word_id.clone(),
             MeiliWord {
                 id: word_id,
                 word: lemma.clone(),
+                language: data.language_of(lexicon).to_string(),
+                lexicon: lexicon.clone(),
                 definitions: Vec::new(),
                 pos: Vec::new(),
                 synonyms: Vec::new(),
@@ -177,7 +148,13 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
                 broader_terms: Vec::new(),
                 narrower_terms: Vec::new(),
                 related_terms: Vec::new(),
+                relations: Vec::new(),
                 examples: Vec::new(),
+                forms: lemma_forms
+                    .get(&(lexicon.clone(), lemma.clone()))
+                    .map(|forms| forms.iter().cloned().collect())
+                    .unwrap_or_default(),
+                content_hash: String::new(),
             },
         );
     }
@@ -204,12 +181,13 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
             .progress_chars("#>-"),
     );
 
-    let words_vec: Vec<(String, HashSet<String>)> = lemma_to_synsets.into_iter().collect();
+    let words_vec: Vec<((String, String), HashSet<String>)> =
+        lemma_to_synsets.into_iter().collect();
 
     let processed_words: Vec<MeiliWord> = words_vec
         .par_iter()
-        .map(|(lemma, synset_ids)| {
-            let word_id = format!("word_{}", normalize_id(lemma));
+        .map(|((lexicon, lemma), synset_ids)| {
+            let word_id = word_id(lexicon, lemma);
             let mut word = match words.get(&word_id) {
                 Some(w) => w.clone(),
                 None => return None,
@@ -228,16 +206,16 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
                     None => continue,
                 };
 
-                if let Some(def) = &synset.definition {
-                    if !def.trim().is_empty() {
-                        let pos_prefix = if !synset.part_of_speech.is_empty() {
-                            format!("({}) ", synset.part_of_speech)
-                        } else {
-                            String::new()
-                        };
-                        word.definitions
-                            .push(format!("{}{}", pos_prefix, def.trim()));
-                    }
+                if let Some(def) = &synset.definition
+                    && !def.trim().is_empty()
+                {
+                    let pos_prefix = if !synset.part_of_speech.is_empty() {
+                        format!("({}) ", synset.part_of_speech)
+                    } else {
+                        String::new()
+                    };
+                    word.definitions
+                        .push(format!("{}{}", pos_prefix, def.trim()));
                 }
 
                 if !synset.part_of_speech.is_empty() {
@@ -264,11 +242,12 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
                     synset,
                     lemma,
                     synset_to_lemmas,
-                    &relation_mapped_counts,
+                    Some(&relation_mapped_counts),
                 );
             }
 
             process_sense_relations_parallel(
+                lexicon,
                 lemma,
                 &mut word,
                 data_senses,
@@ -279,11 +258,14 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
 
             word.pos = pos_set.into_iter().collect();
 
+            deduplicate(&mut word.pos);
             deduplicate(&mut word.synonyms);
             deduplicate(&mut word.antonyms);
             deduplicate(&mut word.broader_terms);
             deduplicate(&mut word.narrower_terms);
             deduplicate(&mut word.related_terms);
+            word.relations.sort();
+            word.relations.dedup();
             deduplicate(&mut word.examples);
             deduplicate(&mut word.definitions);
 
@@ -396,201 +378,534 @@ pub fn transform_to_meilisearch(data: &WordNetData) -> Vec<MeiliWord> {
     result
 }
 
+pub fn transform_to_senses(data: &WordNetData) -> Vec<MeiliSense> {
+    let start_time = Instant::now();
+    println!("Starting sense-level transformation for Meilisearch...");
+
+    let sense_to_lemma = build_sense_to_lemma(data);
+    let synset_to_lemmas = build_synset_to_lemmas(data, &sense_to_lemma);
+
+    let pb = indicatif::ProgressBar::new(data.lexical_entries.len() as u64);
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} entries ({eta})")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let sense_docs: Vec<MeiliSense> = data
+        .lexical_entries
+        .par_iter()
+        .flat_map_iter(|(_, entry)| {
+            let lemma = &entry.lemma.written_form;
+            let mut docs = Vec::new();
+
+            if lemma.trim().is_empty() || lemma.len() > 100 || !is_valid_lemma(lemma) {
+                pb.inc(1);
+                return docs;
+            }
+
+            let word_id = word_id(&entry.lexicon, lemma);
+
+            // Senses are listed in frequency order within an entry, so the
+            // position doubles as the sense number.
+            for (i, sense_id) in entry.senses.iter().enumerate() {
+                let sense = match data.senses.get(sense_id) {
+                    Some(s) => s,
+                    None => continue,
+                };
+                let synset = match data.synsets.get(&sense.synset_id) {
+                    Some(s) => s,
+                    None => continue,
+                };
+
+                let pos = if !synset.part_of_speech.is_empty() {
+                    synset.part_of_speech.clone()
+                } else {
+                    entry.lemma.part_of_speech.clone()
+                };
+
+                let mut doc = MeiliSense {
+                    id: format!("sense_{}_{}", normalize_id(lemma), normalize_id(&synset.id)),
+                    word: lemma.clone(),
+                    language: data.language_of(&entry.lexicon).to_string(),
+                    lexicon: entry.lexicon.clone(),
+                    word_id: word_id.clone(),
+                    sense_id: sense.id.clone(),
+                    synset_id: synset.id.clone(),
+                    ili: synset.ili.clone(),
+                    sense_number: i + 1,
+                    pos,
+                    definition: synset
+                        .definition
+                        .as_ref()
+                        .map(|d| d.trim().to_string())
+                        .filter(|d| !d.is_empty()),
+                    synonyms: Vec::new(),
+                    antonyms: Vec::new(),
+                    broader_terms: Vec::new(),
+                    narrower_terms: Vec::new(),
+                    related_terms: Vec::new(),
+                    relations: Vec::new(),
+                    examples: Vec::new(),
+                    content_hash: String::new(),
+                };
+
+                for example in synset.examples.iter().chain(sense.examples.iter()) {
+                    if !example.trim().is_empty() {
+                        doc.examples.push(example.trim().to_string());
+                    }
+                }
+
+                if let Some(members) = synset_to_lemmas.get(&synset.id) {
+                    for member in members {
+                        if member.to_lowercase() != lemma.to_lowercase() {
+                            doc.synonyms.push(member.clone());
+                        }
+                    }
+                }
+
+                process_synset_relations(&mut doc, synset, lemma, &synset_to_lemmas, None);
+                process_sense_relations(&mut doc, sense, lemma, &sense_to_lemma, None);
+
+                deduplicate(&mut doc.synonyms);
+                deduplicate(&mut doc.antonyms);
+                deduplicate(&mut doc.broader_terms);
+                deduplicate(&mut doc.narrower_terms);
+                deduplicate(&mut doc.related_terms);
+                doc.relations.sort();
+                doc.relations.dedup();
+                deduplicate(&mut doc.examples);
+
+                docs.push(doc);
+            }
+
+            pb.inc(1);
+            docs
+        })
+        .collect();
+
+    pb.finish_with_message("Processing complete");
+
+    let mut seen = HashSet::new();
+    let result: Vec<MeiliSense> = sense_docs
+        .into_iter()
+        .filter(|doc| seen.insert(doc.id.clone()))
+        .collect();
+
+    println!(
+        "Created {} sense documents for Meilisearch in {:.2?}",
+        result.len(),
+        start_time.elapsed()
+    );
+
+    result
+}
+
+/// Groups synsets by their ILI so each concept lists its lemmas in every
+/// loaded language.
+pub fn transform_to_concepts(data: &WordNetData) -> Vec<MeiliConcept> {
+    let start_time = Instant::now();
+    println!("Starting ILI concept transformation for Meilisearch...");
+
+    let sense_to_lemma = build_sense_to_lemma(data);
+    let synset_to_lemmas = build_synset_to_lemmas(data, &sense_to_lemma);
+
+    let mut ili_to_synsets: HashMap<&str, Vec<ConceptSynset>> = HashMap::new();
+
+    for synset in data.synsets.values() {
+        // `in` marks a synset proposed for the ILI but not linked to it yet.
+        let ili = match synset.ili.as_deref() {
+            Some(ili) if !ili.trim().is_empty() && ili != "in" => ili,
+            _ => continue,
+        };
+
+        let lemmas: Vec<String> = synset_to_lemmas
+            .get(&synset.id)
+            .map(|lemmas| {
+                lemmas
+                    .iter()
+                    .filter(|lemma| lemma.len() <= 100 && is_valid_lemma(lemma))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if lemmas.is_empty() {
+            continue;
+        }
+
+        ili_to_synsets.entry(ili).or_default().push(ConceptSynset {
+            synset_id: synset.id.clone(),
+            language: data.language_of(&synset.lexicon).to_string(),
+            lexicon: synset.lexicon.clone(),
+            pos: synset.part_of_speech.clone(),
+            definition: synset
+                .definition
+                .as_ref()
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            lemmas,
+        });
+    }
+
+    let mut result: Vec<MeiliConcept> = ili_to_synsets
+        .into_iter()
+        .map(|(ili, mut synsets)| {
+            synsets.sort_by(|a, b| a.synset_id.cmp(&b.synset_id));
+
+            let mut languages: Vec<String> = synsets
+                .iter()
+                .map(|synset| synset.language.clone())
+                .collect();
+            deduplicate(&mut languages);
+
+            MeiliConcept {
+                id: format!("ili_{}", normalize_id(ili)),
+                ili: ili.to_string(),
+                languages,
+                synsets,
+                content_hash: String::new(),
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let multilingual = result.iter().filter(|c| c.languages.len() > 1).count();
+    println!(
+        "Created {} concept documents ({} linked across languages) in {:.2?}",
+        result.len(),
+        multilingual,
+        start_time.elapsed()
+    );
+
+    result
+}
+
+/// One document per synset with its synset-level relations, so the API can
+/// walk hypernym chains and other relation graphs.
+pub fn transform_to_synsets(data: &WordNetData) -> Vec<MeiliSynset> {
+    let start_time = Instant::now();
+    println!("Starting synset graph transformation for Meilisearch...");
+
+    let sense_to_lemma = build_sense_to_lemma(data);
+    let synset_to_lemmas = build_synset_to_lemmas(data, &sense_to_lemma);
+
+    let mut result: Vec<MeiliSynset> = data
+        .synsets
+        .par_iter()
+        .map(|(synset_id, synset)| {
+            let mut relations: Vec<SynsetEdge> = synset
+                .relations
+                .iter()
+                .filter(|relation| data.synsets.contains_key(&relation.target))
+                .map(|relation| SynsetEdge {
+                    rel_type: relation.rel_type.clone(),
+                    target: relation.target.clone(),
+                })
+                .collect();
+            relations.sort_by(|a, b| {
+                a.rel_type
+                    .cmp(&b.rel_type)
+                    .then_with(|| a.target.cmp(&b.target))
+            });
+            relations.dedup_by(|a, b| a.rel_type == b.rel_type && a.target == b.target);
+
+            MeiliSynset {
+                id: format!("synset_{}", normalize_id(synset_id)),
+                synset_id: synset_id.clone(),
+                language: data.language_of(&synset.lexicon).to_string(),
+                lexicon: synset.lexicon.clone(),
+                ili: synset.ili.clone(),
+                pos: synset.part_of_speech.clone(),
+                definition: synset
+                    .definition
+                    .as_ref()
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty()),
+                lemmas: synset_to_lemmas.get(synset_id).cloned().unwrap_or_default(),
+                relations,
+                content_hash: String::new(),
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let edges: usize = result.iter().map(|s| s.relations.len()).sum();
+    println!(
+        "Created {} synset documents with {} edges in {:.2?}",
+        result.len(),
+        edges,
+        start_time.elapsed()
+    );
+
+    result
+}
+
 fn update_relation_count(counts: &Arc<Mutex<HashMap<String, usize>>>, rel_type: String) {
     let mut counts = counts.lock().unwrap();
     *counts.entry(rel_type).or_insert(0) += 1;
 }
 
-fn process_synset_relations(
-    word: &mut MeiliWord,
+fn process_synset_relations<T: RelationLists>(
+    target: &mut T,
     synset: &crate::models::Synset,
     lemma: &str,
     synset_to_lemmas: &HashMap<String, Vec<String>>,
-    relation_mapped_counts: &Arc<Mutex<HashMap<String, usize>>>,
+    relation_mapped_counts: Option<&Arc<Mutex<HashMap<String, usize>>>>,
 ) {
     for relation in &synset.relations {
-        let target_synset_id = &relation.target;
-        let target_words = match synset_to_lemmas.get(target_synset_id) {
-            Some(members) => members.clone(),
+        let target_words = match synset_to_lemmas.get(&relation.target) {
+            Some(members) => members,
             None => continue,
         };
 
-        if target_words.is_empty() {
-            continue;
+        let bucket = RelationBucket::for_rel_type(&relation.rel_type);
+
+        for target_word in target_words {
+            if target_word.to_lowercase() != lemma.to_lowercase() {
+                target.relation_list(bucket).push(target_word.clone());
+                target.typed_relations().push(TypedRelation {
+                    rel_type: relation.rel_type.clone(),
+                    bucket: bucket.as_str().to_string(),
+                    word: target_word.clone(),
+                });
+                if let Some(counts) = relation_mapped_counts {
+                    update_relation_count(counts, bucket.as_str().to_string());
+                }
+            }
         }
+    }
+}
 
-        match relation.rel_type.as_str() {
-            // ======== BROADER TERMS ========
-            // Hypernym relations (X is a kind of Y)
-            "hypernym" | "instance_hypernym" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.broader_terms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "broader_terms".to_string());
-                    }
+fn process_sense_relations_parallel(
+    source_lexicon: &str,
+    source_lemma: &str,
+    word: &mut MeiliWord,
+    senses: &HashMap<String, crate::models::Sense>,
+    sense_to_lemma: &HashMap<String, String>,
+    lexical_entries: &HashMap<String, crate::models::LexicalEntry>,
+    relation_mapped_counts: &Arc<Mutex<HashMap<String, usize>>>,
+) {
+    let source_sense_ids: Vec<String> = lexical_entries
+        .values()
+        .filter(|le| {
+            le.lexicon == source_lexicon
+                && le.lemma.written_form.to_lowercase() == source_lemma.to_lowercase()
+        })
+        .flat_map(|le| le.senses.clone())
+        .collect();
+
+    for sense_id in &source_sense_ids {
+        if let Some(sense) = senses.get(sense_id) {
+            // Add examples from this sense
+            for example in &sense.examples {
+                if !example.trim().is_empty() {
+                    word.examples.push(example.clone());
                 }
             }
+
+            process_sense_relations(
+                word,
+                sense,
+                source_lemma,
+                sense_to_lemma,
+                Some(relation_mapped_counts),
+            );
+        }
+    }
+}
+
+fn process_sense_relations<T: RelationLists>(
+    target: &mut T,
+    sense: &crate::models::Sense,
+    source_lemma: &str,
+    sense_to_lemma: &HashMap<String, String>,
+    relation_mapped_counts: Option<&Arc<Mutex<HashMap<String, usize>>>>,
+) {
+    for relation in &sense.relations {
+        if let Some(target_lemma) = sense_to_lemma.get(&relation.target) {
+            if target_lemma.trim().is_empty()
+                || target_lemma.to_lowercase() == source_lemma.to_lowercase()
+            {
+                continue;
+            }
+
+            let bucket = RelationBucket::for_rel_type(&relation.rel_type);
+            target.relation_list(bucket).push(target_lemma.clone());
+            target.typed_relations().push(TypedRelation {
+                rel_type: relation.rel_type.clone(),
+                bucket: bucket.as_str().to_string(),
+                word: target_lemma.clone(),
+            });
+            if let Some(counts) = relation_mapped_counts {
+                update_relation_count(counts, bucket.as_str().to_string());
+            }
+        }
+    }
+}
+
+/// The five coarse relation lists every indexed document carries.
+#[derive(Debug, Clone, Copy)]
+enum RelationBucket {
+    Synonyms,
+    Antonyms,
+    BroaderTerms,
+    NarrowerTerms,
+    RelatedTerms,
+}
+
+impl RelationBucket {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RelationBucket::Synonyms => "synonyms",
+            RelationBucket::Antonyms => "antonyms",
+            RelationBucket::BroaderTerms => "broader_terms",
+            RelationBucket::NarrowerTerms => "narrower_terms",
+            RelationBucket::RelatedTerms => "related_terms",
+        }
+    }
+
+    /// The one place a WordNet `relType` is mapped to a coarse list, for
+    /// synset and sense relations alike.
+    fn for_rel_type(rel_type: &str) -> Self {
+        match rel_type {
+            // ======== BROADER TERMS ========
+            // Hypernym relations (X is a kind of Y)
+            "hypernym" | "instance_hypernym" => RelationBucket::BroaderTerms,
             // Holonym relations (X is part of Y)
             "holo_member" | "holo_part" | "holo_substance" | "part_holonym" | "member_holonym"
             | "substance_holonym" | "holo_location" | "holo_portion" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.broader_terms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "broader_terms".to_string());
-                    }
-                }
+                RelationBucket::BroaderTerms
             }
 
             // ======== NARROWER TERMS ========
             // Hyponym relations (Y is a kind of X)
-            "hyponym" | "instance_hyponym" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.narrower_terms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "narrower_terms".to_string());
-                    }
-                }
-            }
+            "hyponym" | "instance_hyponym" => RelationBucket::NarrowerTerms,
             // Meronym relations (Y is part of X)
             "mero_member" | "mero_part" | "mero_substance" | "part_meronym" | "member_meronym"
             | "substance_meronym" | "mero_location" | "mero_portion" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.narrower_terms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "narrower_terms".to_string());
-                    }
-                }
+                RelationBucket::NarrowerTerms
             }
 
             // ======== ANTONYMS ========
             // Opposition relationships
             "antonym" | "anto_gradable" | "anto_simple" | "anto_converse" | "near_antonym" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.antonyms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "antonyms".to_string());
-                    }
-                }
+                RelationBucket::Antonyms
             }
 
             // ======== SYNONYMS ========
             // Similarity relationships
-            "similar" | "also" | "verb_group" | "eq_synonym" | "ir_synonym" => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.synonyms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "synonyms".to_string());
-                    }
-                }
-            }
+            "similar" | "also" | "verb_group" | "eq_synonym" | "ir_synonym" | "similar_to"
+            | "see_also" => RelationBucket::Synonyms,
 
             // ======== RELATED TERMS ========
-            // All other relationship types
-            _ => {
-                for target_word in target_words {
-                    if target_word.to_lowercase() != lemma.to_lowercase() {
-                        word.related_terms.push(target_word.clone());
-                        update_relation_count(relation_mapped_counts, "related_terms".to_string());
-                    }
-                }
-            }
+            // Derivational and semantic (participle, derivation, pertainym, domains, ...)
+            _ => RelationBucket::RelatedTerms,
         }
     }
 }
 
-fn process_sense_relations_parallel(
-    source_lemma: &str,
-    word: &mut MeiliWord,
-    senses: &HashMap<String, crate::models::Sense>,
-    sense_to_lemma: &HashMap<String, String>,
-    lexical_entries: &HashMap<String, crate::models::LexicalEntry>,
-    relation_mapped_counts: &Arc<Mutex<HashMap<String, usize>>>,
-) {
-    let source_sense_ids: Vec<String> = lexical_entries
-        .values()
-        .filter(|le| le.lemma.written_form.to_lowercase() == source_lemma.to_lowercase())
-        .flat_map(|le| le.senses.clone())
-        .collect();
+trait RelationLists {
+    fn relation_list(&mut self, bucket: RelationBucket) -> &mut Vec<String>;
+    fn typed_relations(&mut self) -> &mut Vec<TypedRelation>;
+}
 
-    for sense_id in &source_sense_ids {
-        if let Some(sense) = senses.get(sense_id) {
-            // Add examples from this sense
-            for example in &sense.examples {
-                if !example.trim().is_empty() {
-                    word.examples.push(example.clone());
-                }
-            }
+impl RelationLists for MeiliWord {
+    fn relation_list(&mut self, bucket: RelationBucket) -> &mut Vec<String> {
+        match bucket {
+            RelationBucket::Synonyms => &mut self.synonyms,
+            RelationBucket::Antonyms => &mut self.antonyms,
+            RelationBucket::BroaderTerms => &mut self.broader_terms,
+            RelationBucket::NarrowerTerms => &mut self.narrower_terms,
+            RelationBucket::RelatedTerms => &mut self.related_terms,
+        }
+    }
 
-            for relation in &sense.relations {
-                if let Some(target_lemma) = sense_to_lemma.get(&relation.target) {
-                    if target_lemma.trim().is_empty()
-                        || target_lemma.to_lowercase() == source_lemma.to_lowercase()
-                    {
-                        continue;
-                    }
+    fn typed_relations(&mut self) -> &mut Vec<TypedRelation> {
+        &mut self.relations
+    }
+}
 
-                    match relation.rel_type.as_str() {
-                        // ======== ANTONYMS ========
-                        "antonym" | "anto_gradable" | "anto_simple" | "anto_converse"
-                        | "near_antonym" => {
-                            word.antonyms.push(target_lemma.clone());
-                            update_relation_count(relation_mapped_counts, "antonyms".to_string());
-                        }
+impl RelationLists for MeiliSense {
+    fn relation_list(&mut self, bucket: RelationBucket) -> &mut Vec<String> {
+        match bucket {
+            RelationBucket::Synonyms => &mut self.synonyms,
+            RelationBucket::Antonyms => &mut self.antonyms,
+            RelationBucket::BroaderTerms => &mut self.broader_terms,
+            RelationBucket::NarrowerTerms => &mut self.narrower_terms,
+            RelationBucket::RelatedTerms => &mut self.related_terms,
+        }
+    }
 
-                        // ======== SYNONYMS ========
-                        "similar" | "also" | "verb_group" | "similar_to" | "see_also" => {
-                            word.synonyms.push(target_lemma.clone());
-                            update_relation_count(relation_mapped_counts, "synonyms".to_string());
-                        }
+    fn typed_relations(&mut self) -> &mut Vec<TypedRelation> {
+        &mut self.relations
+    }
+}
 
-                        // ======== BROADER TERMS ========
-                        "hypernym" | "instance_hypernym" => {
-                            word.broader_terms.push(target_lemma.clone());
-                            update_relation_count(
-                                relation_mapped_counts,
-                                "broader_terms".to_string(),
-                            );
-                        }
+fn build_sense_to_lemma(data: &WordNetData) -> HashMap<String, String> {
+    println!("Building sense to lemma mapping...");
+    let mut sense_to_lemma: HashMap<String, String> = HashMap::new();
 
-                        // ======== NARROWER TERMS ========
-                        "hyponym" | "instance_hyponym" => {
-                            word.narrower_terms.push(target_lemma.clone());
-                            update_relation_count(
-                                relation_mapped_counts,
-                                "narrower_terms".to_string(),
-                            );
-                        }
+    for le in data.lexical_entries.values() {
+        if le.lemma.written_form.trim().is_empty() {
+            continue;
+        }
 
-                        // ======== RELATED TERMS ========
-                        // Derivational and semantic
-                        "participle"
-                        | "derivation"
-                        | "pertainym"
-                        | "domain_topic"
-                        | "domain_member_topic"
-                        | "domain_region"
-                        | "domain_member_region"
-                        | "exemplifies"
-                        | "is_exemplified_by" => {
-                            word.related_terms.push(target_lemma.clone());
-                            update_relation_count(
-                                relation_mapped_counts,
-                                "related_terms".to_string(),
-                            );
-                        }
+        for sense_id in &le.senses {
+            sense_to_lemma.insert(sense_id.clone(), le.lemma.written_form.clone());
+        }
+    }
 
-                        _ => {
-                            word.related_terms.push(target_lemma.clone());
-                            update_relation_count(
-                                relation_mapped_counts,
-                                "related_terms".to_string(),
-                            );
-                        }
-                    }
-                }
+    println!("Created mapping for {} senses", sense_to_lemma.len());
+
+    sense_to_lemma
+}
+
+fn build_synset_to_lemmas(
+    data: &WordNetData,
+    sense_to_lemma: &HashMap<String, String>,
+) -> HashMap<String, Vec<String>> {
+    println!("Building synset to lemmas mapping...");
+    let mut synset_to_lemmas: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (sense_id, sense) in &data.senses {
+        if let Some(lemma) = sense_to_lemma.get(sense_id)
+            && !lemma.trim().is_empty()
+        {
+            synset_to_lemmas
+                .entry(sense.synset_id.clone())
+                .or_default()
+                .push(lemma.clone());
+        }
+    }
+
+    for (synset_id, synset) in &data.synsets {
+        for member_id in &synset.members {
+            if let Some(lemma) = sense_to_lemma.get(member_id)
+                && !lemma.trim().is_empty()
+            {
+                synset_to_lemmas
+                    .entry(synset_id.clone())
+                    .or_default()
+                    .push(lemma.clone());
             }
         }
     }
+
+    for lemmas in synset_to_lemmas.values_mut() {
+        lemmas.sort();
+        lemmas.dedup();
+    }
+
+    println!(
+        "Created synset to lemmas mapping for {} synsets",
+        synset_to_lemmas.len()
+    );
+
+    synset_to_lemmas
 }
 
 fn deduplicate(vec: &mut Vec<String>) {
@@ -598,6 +913,10 @@ fn deduplicate(vec: &mut Vec<String>) {
     vec.dedup();
 }
 
+fn word_id(lexicon: &str, lemma: &str) -> String {
+    format!("word_{}_{}", normalize_id(lexicon), normalize_id(lemma))
+}
+
 fn normalize_id(s: &str) -> String {
     s.to_lowercase()
         .replace([' ', '-'], "_")